@@ -12,6 +12,8 @@ pub enum SentcError
 
 	UsernameOrPasswordRequired,
 	UserMfaRequired,
+
+	GroupOwnerDemoteFailed(Box<SentcError>),
 }
 
 impl From<SdkLightError> for SentcError
@@ -0,0 +1,76 @@
+use std::future::Future;
+
+use futures_util::{stream, StreamExt};
+use sentc_crypto_light::sdk_common::UserId;
+
+use crate::error::SentcError;
+use crate::group::Group;
+use crate::net_helper::check_jwt;
+
+/// The result of a bulk action for one user.
+///
+/// The results are in the same order as the user ids.
+#[derive(Debug)]
+pub struct GroupBulkResult
+{
+	pub user_id: UserId,
+	pub result: Result<(), SentcError>,
+}
+
+impl Group
+{
+	/// Invite many user at once with max. limit requests at the same time.
+	pub async fn invite_many(
+		&self,
+		jwt: &str,
+		user_ids: &[&str],
+		rank: Option<i32>,
+		auto: bool,
+		limit: usize,
+	) -> Result<Vec<GroupBulkResult>, SentcError>
+	{
+		check_jwt(jwt)?;
+
+		Ok(Self::run_bulk(user_ids, limit, |user_id| {
+			self.invite_user_internally(jwt, user_id, rank, auto, false)
+		})
+		.await)
+	}
+
+	/// Kick many user at once with max. limit requests at the same time.
+	pub async fn kick_many(&self, jwt: &str, user_ids: &[&str], limit: usize) -> Result<Vec<GroupBulkResult>, SentcError>
+	{
+		check_jwt(jwt)?;
+
+		Ok(Self::run_bulk(user_ids, limit, |user_id| self.kick_user(jwt, user_id)).await)
+	}
+
+	/// Set the same rank for many user with max. limit requests at the same time.
+	pub async fn update_rank_many(&self, jwt: &str, user_ids: &[&str], new_rank: i32, limit: usize) -> Result<Vec<GroupBulkResult>, SentcError>
+	{
+		check_jwt(jwt)?;
+
+		Ok(Self::run_bulk(user_ids, limit, |user_id| self.update_rank(jwt, user_id, new_rank)).await)
+	}
+
+	async fn run_bulk<'a, F, Fut>(user_ids: &[&'a str], limit: usize, action: F) -> Vec<GroupBulkResult>
+	where
+		F: Fn(&'a str) -> Fut,
+		Fut: Future<Output = Result<(), SentcError>>,
+	{
+		stream::iter(user_ids.iter().copied())
+			.map(|user_id| {
+				let res = action(user_id);
+
+				async move {
+					GroupBulkResult {
+						user_id: user_id.to_string(),
+						result: res.await,
+					}
+				}
+			})
+			.buffered(limit.max(1))
+			.collect()
+			.await
+	}
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "network")]
+pub mod bulk;
 mod export;
 #[cfg(feature = "network")]
 pub mod net;
@@ -1,6 +1,17 @@
 use std::future::Future;
 
-use sentc_crypto_light::sdk_common::group::{GroupChildrenList, GroupInviteReqList, GroupJoinReqList, ListGroups};
+use futures_util::Stream;
+use sentc_crypto_light::error::SdkLightError;
+use sentc_crypto_light::sdk_common::group::{
+	GroupChangeRankServerInput,
+	GroupChildrenList,
+	GroupInviteReqList,
+	GroupJoinReqList,
+	GroupUserListItem,
+	ListGroups,
+};
+use sentc_crypto_light::sdk_utils::handle_general_server_response;
+use sentc_crypto_light::sdk_utils::http::{make_req, HttpMethod};
 use sentc_crypto_light::util_req_full::group::{
 	accept_invite,
 	accept_join_req,
@@ -14,6 +25,7 @@ use sentc_crypto_light::util_req_full::group::{
 	get_groups_for_user,
 	get_invites_for_user,
 	get_join_reqs,
+	get_member,
 	get_sent_join_req,
 	invite_user,
 	join_req,
@@ -27,7 +39,7 @@ use sentc_crypto_light::util_req_full::group::{
 
 use crate::error::SentcError;
 use crate::group::Group;
-use crate::net_helper::check_jwt;
+use crate::net_helper::{check_jwt, list_stream};
 
 impl Group
 {
@@ -53,6 +65,18 @@ impl Group
 		.await?)
 	}
 
+	/// Like get_children but fetches all pages.
+	pub fn get_children_stream<'a>(&'a self, jwt: &'a str) -> impl Stream<Item = Result<GroupChildrenList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_children(jwt, pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub fn get_child_group<'a>(&'a self, group_id: &'a str, jwt: &'a str) -> impl Future<Output = Result<Self, SentcError>> + 'a
 	{
 		Group::fetch_group(
@@ -147,6 +171,9 @@ impl Group
 		.await?)
 	}
 
+	/// Set the rank of the user in this group, e.g. after an admin changed the rank.
+	///
+	/// No request is sent, use group_update_check to fetch the rank from the server.
 	pub async fn update_own_rank(&mut self, jwt: &str, new_rank: i32) -> Result<(), SentcError>
 	{
 		check_jwt(jwt)?;
@@ -179,6 +206,50 @@ impl Group
 		.await?)
 	}
 
+	/// Hand the group over to another member, e.g. before the creator leaves the group.
+	///
+	/// The new owner gets the creator rank (0), then the own rank is changed to admin (1).
+	/// own_id is the user id of this member (or the id of the group for a connected group).
+	///
+	/// If only the demotion failed, both are creators and SentcError::GroupOwnerDemoteFailed is returned.
+	/// Change the own rank again with update_rank.
+	pub async fn transfer_ownership(&mut self, jwt: &str, own_id: &str, new_owner_id: &str) -> Result<(), SentcError>
+	{
+		check_jwt(jwt)?;
+
+		if self.rank != 0 {
+			return Err(SdkLightError::GroupPermission.into());
+		}
+
+		//the sdk only allows the ranks 1 to 4 for a rank change, so the request for the creator rank is done here
+		let url = self.base_url.clone() + "/api/v1/group/" + self.get_group_id() + "/change_rank";
+
+		let input = serde_json::to_string(&GroupChangeRankServerInput {
+			changed_user_id: new_owner_id.to_string(),
+			new_rank: 0,
+		})?;
+
+		let res = make_req(
+			HttpMethod::PUT,
+			&url,
+			&self.app_token,
+			Some(input),
+			Some(jwt),
+			self.access_by_group_as_member.as_deref(),
+		)
+		.await?;
+
+		handle_general_server_response(&res)?;
+
+		self.update_rank_internally(own_id, jwt, 1)
+			.await
+			.map_err(|e| SentcError::GroupOwnerDemoteFailed(Box::new(e)))?;
+
+		self.rank = 1;
+
+		Ok(())
+	}
+
 	//______________________________________________________________________________________________
 
 	pub async fn leave(&self, jwt: &str) -> Result<(), SentcError>
@@ -195,6 +266,42 @@ impl Group
 		.await?)
 	}
 
+	//______________________________________________________________________________________________
+
+	pub async fn get_member(&self, jwt: &str, last_item: Option<&GroupUserListItem>) -> Result<Vec<GroupUserListItem>, SentcError>
+	{
+		check_jwt(jwt)?;
+
+		let (last_time, last_id) = if let Some(li) = last_item {
+			(li.joined_time, li.user_id.as_str())
+		} else {
+			(0, "none")
+		};
+
+		Ok(get_member(
+			self.base_url.clone(),
+			&self.app_token,
+			jwt,
+			self.get_group_id(),
+			last_time.to_string().as_str(),
+			last_id,
+			self.access_by_group_as_member.as_deref(),
+		)
+		.await?)
+	}
+
+	/// Like get_member but fetches all pages.
+	pub fn get_member_stream<'a>(&'a self, jwt: &'a str) -> impl Stream<Item = Result<GroupUserListItem, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_member(jwt, pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	//______________________________________________________________________________________________
 	//group as member
 
@@ -219,6 +326,18 @@ impl Group
 		.await?)
 	}
 
+	/// Like get_groups but fetches all pages.
+	pub fn get_groups_stream<'a>(&'a self, jwt: &'a str) -> impl Stream<Item = Result<ListGroups, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_groups(jwt, pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn get_group_invites(&self, jwt: &str, last_item: Option<&GroupInviteReqList>) -> Result<Vec<GroupInviteReqList>, SentcError>
 	{
 		check_jwt(jwt)?;
@@ -241,6 +360,18 @@ impl Group
 		.await?)
 	}
 
+	/// Like get_group_invites but fetches all pages.
+	pub fn get_group_invites_stream<'a>(&'a self, jwt: &'a str) -> impl Stream<Item = Result<GroupInviteReqList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_group_invites(jwt, pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn accept_group_invite(&self, jwt: &str, group_id_to_accept: &str) -> Result<(), SentcError>
 	{
 		check_jwt(jwt)?;
@@ -314,6 +445,18 @@ impl Group
 		.await?)
 	}
 
+	/// Like get_group_sent_join_req but fetches all pages.
+	pub fn get_group_sent_join_req_stream<'a>(&'a self, jwt: &'a str) -> impl Stream<Item = Result<GroupInviteReqList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_group_sent_join_req(jwt, pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn delete_join_req(&self, jwt: &str, id: &str) -> Result<(), SentcError>
 	{
 		check_jwt(jwt)?;
@@ -368,7 +511,8 @@ impl Group
 		self.invite_user_internally(jwt, user_id, rank, true, true)
 	}
 
-	async fn invite_user_internally(&self, jwt: &str, user_id: &str, rank: Option<i32>, auto: bool, group: bool) -> Result<(), SentcError>
+	pub(crate) async fn invite_user_internally(&self, jwt: &str, user_id: &str, rank: Option<i32>, auto: bool, group: bool)
+		-> Result<(), SentcError>
 	{
 		check_jwt(jwt)?;
 
@@ -415,6 +559,18 @@ impl Group
 		.await?)
 	}
 
+	/// Like get_join_requests but fetches all pages.
+	pub fn get_join_requests_stream<'a>(&'a self, jwt: &'a str) -> impl Stream<Item = Result<GroupJoinReqList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_join_requests(jwt, pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn reject_join_request(&self, jwt: &str, id_to_reject: &str) -> Result<(), SentcError>
 	{
 		check_jwt(jwt)?;
@@ -1,5 +1,8 @@
+use std::collections::VecDeque;
+use std::future::Future;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use futures_util::stream::{try_unfold, Stream};
 use sentc_crypto_light::util_req_full::decode_jwt;
 
 use crate::error::SentcError;
@@ -27,3 +30,143 @@ pub(crate) fn check_jwt(jwt: &str) -> Result<(), SentcError>
 
 	Ok(())
 }
+
+/// The state of a list stream.
+///
+/// The last item of a page is the cursor for the next page,
+/// so it is held back until the next page was fetched.
+pub(crate) struct ListPages<T>
+{
+	items: VecDeque<T>,
+	last: Option<T>,
+	done: bool,
+}
+
+impl<T> ListPages<T>
+{
+	pub(crate) fn last_item(&self) -> Option<&T>
+	{
+		self.last.as_ref()
+	}
+
+	fn push_page(&mut self, mut page: Vec<T>)
+	{
+		//the server page size is not known, so only an empty page ends the list
+		if page.is_empty() {
+			self.done = true;
+		}
+
+		if let Some(last) = page.pop() {
+			if let Some(prev_last) = self.last.replace(last) {
+				self.items.push_back(prev_last);
+			}
+
+			self.items.extend(page);
+		}
+	}
+
+	fn next_item(&mut self) -> Option<T>
+	{
+		match self.items.pop_front() {
+			Some(item) => Some(item),
+			None if self.done => self.last.take(),
+			None => None,
+		}
+	}
+}
+
+/// Fetch the pages of a list endpoint until the list is exhausted.
+///
+/// fetch gets the state of the stream to get the last item for the next page and returns the state and the fetched page.
+/// The stream ends with the first empty page.
+pub(crate) fn list_stream<'a, T, F, Fut>(fetch: F) -> impl Stream<Item = Result<T, SentcError>> + 'a
+where
+	T: 'a,
+	F: FnMut(ListPages<T>) -> Fut + 'a,
+	Fut: Future<Output = Result<(ListPages<T>, Vec<T>), SentcError>> + 'a,
+{
+	let pages = ListPages {
+		items: VecDeque::new(),
+		last: None,
+		done: false,
+	};
+
+	try_unfold((pages, fetch), |(mut pages, mut fetch)| {
+		async move {
+			//a page with only one item gives no item until the next page was fetched,
+			//because the last item is held back as cursor
+			while pages.items.is_empty() && !pages.done {
+				let (fetched_pages, page) = fetch(pages).await?;
+
+				pages = fetched_pages;
+				pages.push_page(page);
+			}
+
+			Ok(pages.next_item().map(|item| (item, (pages, fetch))))
+		}
+	})
+}
+
+#[cfg(test)]
+mod test
+{
+	use futures_util::TryStreamExt;
+
+	use super::*;
+
+	//a server with a fixed page size, the items are 0..len and the cursor is the last fetched item
+	async fn fetch_all(len: usize, page_size: usize) -> (Vec<usize>, usize)
+	{
+		let mut requests = 0;
+
+		let items = list_stream(|pages: ListPages<usize>| {
+			requests += 1;
+
+			let start = pages.last_item().map_or(0, |last| last + 1);
+			let page: Vec<usize> = (start..len).take(page_size).collect();
+
+			async move { Ok((pages, page)) }
+		})
+		.try_collect::<Vec<_>>()
+		.await
+		.unwrap();
+
+		(items, requests)
+	}
+
+	#[tokio::test]
+	async fn test_list_stream_without_items()
+	{
+		let (items, requests) = fetch_all(0, 3).await;
+
+		assert!(items.is_empty());
+		assert_eq!(requests, 1);
+	}
+
+	#[tokio::test]
+	async fn test_list_stream_with_one_item()
+	{
+		let (items, requests) = fetch_all(1, 3).await;
+
+		assert_eq!(items, vec![0]);
+		assert_eq!(requests, 2);
+	}
+
+	#[tokio::test]
+	async fn test_list_stream_with_a_full_page()
+	{
+		let (items, requests) = fetch_all(3, 3).await;
+
+		assert_eq!(items, vec![0, 1, 2]);
+		assert_eq!(requests, 2);
+	}
+
+	#[tokio::test]
+	async fn test_list_stream_with_many_pages()
+	{
+		let (items, requests) = fetch_all(7, 3).await;
+
+		assert_eq!(items, (0..7).collect::<Vec<_>>());
+		assert_eq!(requests, 4);
+	}
+}
@@ -1,5 +1,6 @@
 use std::future::Future;
 
+use futures_util::Stream;
 use sentc_crypto_light::sdk_common::group::{GroupInviteReqList, ListGroups};
 use sentc_crypto_light::sdk_common::user::{OtpRegister, UserDeviceList};
 use sentc_crypto_light::sdk_common::GroupId;
@@ -35,7 +36,7 @@ use sentc_crypto_light::util_req_full::user::{
 
 use crate::error::SentcError;
 use crate::group::Group;
-use crate::net_helper::check_jwt;
+use crate::net_helper::{check_jwt, list_stream};
 use crate::user::User;
 
 #[allow(clippy::large_enum_variant)]
@@ -89,6 +90,18 @@ impl User
 		.await?)
 	}
 
+	/// Like get_groups but fetches all pages.
+	pub fn get_groups_stream<'a>(&'a self) -> impl Stream<Item = Result<ListGroups, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_groups(pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn get_group_invites(&self, last_item: Option<&GroupInviteReqList>) -> Result<Vec<GroupInviteReqList>, SentcError>
 	{
 		check_jwt(&self.jwt)?;
@@ -111,6 +124,18 @@ impl User
 		.await?)
 	}
 
+	/// Like get_group_invites but fetches all pages.
+	pub fn get_group_invites_stream<'a>(&'a self) -> impl Stream<Item = Result<GroupInviteReqList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_group_invites(pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn accept_group_invite(&self, group_id_to_accept: &str) -> Result<(), SentcError>
 	{
 		check_jwt(&self.jwt)?;
@@ -195,6 +220,18 @@ impl User
 		.await?)
 	}
 
+	/// Like get_sent_join_req but fetches all pages.
+	pub fn get_sent_join_req_stream<'a>(&'a self) -> impl Stream<Item = Result<GroupInviteReqList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_sent_join_req(pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	//==============================================================================================
 	//otp
 
@@ -392,6 +429,18 @@ impl User
 		.await?)
 	}
 
+	/// Like get_devices but fetches all pages.
+	pub fn get_devices_stream<'a>(&'a self) -> impl Stream<Item = Result<UserDeviceList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_devices(pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	//==============================================================================================
 
 	async fn get_fresh_jwt(&self, username: &str, password: &str, mfa_token: Option<String>, mfa_recovery: Option<bool>)
@@ -1,5 +1,6 @@
 use std::ops::{Deref, DerefMut};
 
+use futures_util::TryStreamExt;
 use sentc_crypto_light::error::SdkLightError;
 use sentc_light::error::SentcError;
 use sentc_light::group::Group;
@@ -506,6 +507,36 @@ async fn test_29_increase_rank_for_user_1()
 	g.group_update_check(u1.get_jwt().unwrap()).await.unwrap();
 }
 
+#[tokio::test]
+async fn test_29_x_get_member()
+{
+	let u = USER_1_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_2_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let member = g.get_member(u0.get_jwt().unwrap(), None).await.unwrap();
+
+	assert_eq!(member.len(), 3);
+
+	for m in &member {
+		if m.user_id == u.get_user_id() {
+			assert_eq!(m.rank, 1);
+		} else if m.user_id == u1.get_user_id() {
+			assert_eq!(m.rank, 2);
+		}
+	}
+
+	let all: Vec<_> = g
+		.get_member_stream(u0.get_jwt().unwrap())
+		.try_collect()
+		.await
+		.unwrap();
+
+	assert_eq!(all.len(), member.len());
+}
+
 #[tokio::test]
 async fn test_30_not_kick_a_user_with_higher_rank()
 {
@@ -552,6 +583,48 @@ async fn test_32_not_get_the_group_after_kick()
 	}
 }
 
+#[tokio::test]
+async fn test_32_x_invite_update_rank_and_kick_many()
+{
+	let u = USER_2_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let res = g
+		.invite_many(u0.get_jwt().unwrap(), &[u.get_user_id()], None, true, 2)
+		.await
+		.unwrap();
+
+	assert_eq!(res.len(), 1);
+	assert_eq!(res[0].user_id, u.get_user_id());
+	assert!(res[0].result.is_ok());
+
+	let res = g
+		.update_rank_many(u0.get_jwt().unwrap(), &[u.get_user_id()], 3, 2)
+		.await
+		.unwrap();
+
+	assert!(res[0].result.is_ok());
+
+	let member = g.get_member(u0.get_jwt().unwrap(), None).await.unwrap();
+
+	let m = member
+		.iter()
+		.find(|m| m.user_id == u.get_user_id())
+		.unwrap();
+
+	assert_eq!(m.rank, 3);
+
+	let res = g
+		.kick_many(u0.get_jwt().unwrap(), &[u.get_user_id()], 2)
+		.await
+		.unwrap();
+
+	assert!(res[0].result.is_ok());
+
+	assert!(u.get_group(g.get_group_id(), None).await.is_err());
+}
+
 //__________________________________________________________________________________________________
 //child group
 
@@ -646,6 +719,57 @@ async fn test_36_get_child_group_by_direct_access()
 		.await;
 }
 
+#[tokio::test]
+async fn test_37_transfer_ownership()
+{
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u = USER_3_TEST_STATE.get().unwrap().read().await;
+
+	let group_id = u0.create_group().await.unwrap();
+
+	let mut g = u0.get_group(&group_id, None).await.unwrap();
+
+	g.invite_auto(u0.get_jwt().unwrap(), u.get_user_id(), None)
+		.await
+		.unwrap();
+
+	let mut g3 = u.get_group(&group_id, None).await.unwrap();
+
+	//only the creator can hand over the group
+	match g3
+		.transfer_ownership(u.get_jwt().unwrap(), u.get_user_id(), u0.get_user_id())
+		.await
+	{
+		Err(SentcError::Sdk(SdkLightError::GroupPermission)) => {},
+		_ => panic!("should be error"),
+	}
+
+	g.transfer_ownership(u0.get_jwt().unwrap(), u0.get_user_id(), u.get_user_id())
+		.await
+		.unwrap();
+
+	assert_eq!(g.get_rank(), 1);
+
+	let member = g.get_member(u0.get_jwt().unwrap(), None).await.unwrap();
+
+	for m in &member {
+		if m.user_id == u.get_user_id() {
+			assert_eq!(m.rank, 0);
+		} else if m.user_id == u0.get_user_id() {
+			assert_eq!(m.rank, 1);
+		}
+	}
+
+	g3.group_update_check(u.get_jwt().unwrap()).await.unwrap();
+
+	assert_eq!(g3.get_rank(), 0);
+
+	//the old creator is not the creator anymore and can leave the group
+	g.leave(u0.get_jwt().unwrap()).await.unwrap();
+
+	g3.delete_group(u.get_jwt().unwrap()).await.unwrap();
+}
+
 #[tokio::test]
 async fn zzz_clean_up()
 {
@@ -1,5 +1,6 @@
 use std::ops::{Deref, DerefMut};
 
+use futures_util::TryStreamExt;
 use sentc_crypto_light::error::SdkLightError;
 use sentc_light::error::SentcError;
 use sentc_light::group::Group;
@@ -421,6 +422,14 @@ async fn test_26_get_all_connected_groups()
 		.unwrap();
 
 	assert_eq!(list_2.len(), 0);
+
+	let list: Vec<_> = g1
+		.get_groups_stream(u1.get_jwt().unwrap())
+		.try_collect()
+		.await
+		.unwrap();
+
+	assert_eq!(list.len(), 1);
 }
 
 #[tokio::test]
@@ -0,0 +1,209 @@
+use std::borrow::Cow;
+
+use base64ct::{Base64, Encoding};
+use sentc_crypto::sdk_common::crypto::EncryptedHead;
+use sentc_crypto::sdk_utils::error::SdkUtilError;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SentcError;
+
+/// Options to transform the data before encryption.
+///
+/// The used options are stored in the head of the encrypted data, so decrypt_sync can undo them without any options.
+///
+/// The head is not encrypted and not signed, so the options in the head are not authenticated.
+/// The encrypted data itself can't be changed, but a changed head can remove or add the flags.
+/// Then decrypt returns the still padded or compressed data or fails.
+/// Don't use the options if the exact data must be detected as changed by the signature only.
+#[derive(Default, Clone)]
+pub struct EncryptOptions
+{
+	/// Compress the data with zstd before encryption. Requires the compression feature.
+	/// Decrypt fails for data larger than 64 MiB after decompression.
+	pub compress: bool,
+	/// Pad the data to a multiple of this size to hide the exact length of the data.
+	pub pad_to: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Compression
+{
+	Zstd,
+}
+
+/// The head of the encrypted data with the options which were used before encryption.
+///
+/// The fields of the normal head are flatten, so data without options can be read with this head too.
+#[derive(Serialize, Deserialize)]
+pub struct OptionsEncryptedHead
+{
+	#[serde(flatten)]
+	pub head: EncryptedHead,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub compression: Option<Compression>,
+	#[serde(default, skip_serializing_if = "core::ops::Not::not")]
+	pub padded: bool,
+}
+
+impl OptionsEncryptedHead
+{
+	pub fn new(head: EncryptedHead, options: &EncryptOptions) -> Self
+	{
+		Self {
+			head,
+			compression: if options.compress { Some(Compression::Zstd) } else { None },
+			padded: options.pad_to.is_some(),
+		}
+	}
+}
+
+//__________________________________________________________________________________________________
+
+#[cfg(feature = "compression")]
+const ZSTD_LEVEL: i32 = 3;
+
+/// The max size of the decompressed data, so a small encrypted input can't fill the memory.
+#[cfg(feature = "compression")]
+const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Prepare the data for encryption. First compress then pad.
+pub(crate) fn apply_options<'a>(data: &'a [u8], options: &EncryptOptions) -> Result<Cow<'a, [u8]>, SentcError>
+{
+	let mut data = Cow::Borrowed(data);
+
+	if options.compress {
+		data = Cow::Owned(compress(&data)?);
+	}
+
+	if let Some(pad_to) = options.pad_to {
+		data = Cow::Owned(pad(&data, pad_to));
+	}
+
+	Ok(data)
+}
+
+/// Undo the options after decryption. Data without options is returned as it is.
+pub(crate) fn undo_options(mut data: Vec<u8>, head: &OptionsEncryptedHead) -> Result<Vec<u8>, SentcError>
+{
+	if head.padded {
+		let len = unpad(&data)?;
+		data.truncate(len);
+	}
+
+	if let Some(Compression::Zstd) = head.compression {
+		data = decompress(&data)?;
+	}
+
+	Ok(data)
+}
+
+/// Encode the encrypted data with the options head like the sdk encodes encrypted strings.
+pub(crate) fn encode_string(data: &[u8]) -> String
+{
+	Base64::encode_string(data)
+}
+
+pub(crate) fn decode_string(data: &str) -> Result<Vec<u8>, SentcError>
+{
+	Ok(Base64::decode_vec(data).map_err(|_| SdkUtilError::DecodeEncryptedDataFailed)?)
+}
+
+pub(crate) fn decrypted_to_string(data: Vec<u8>) -> Result<String, SentcError>
+{
+	Ok(String::from_utf8(data).map_err(|_| SdkUtilError::DecodeEncryptedDataFailed)?)
+}
+
+/// Iso 7816-4 padding: a 0x80 byte and then zeros until the next multiple of pad_to.
+///
+/// The marker is always added, so there is always at least one byte of padding.
+fn pad(data: &[u8], pad_to: usize) -> Vec<u8>
+{
+	let pad_to = pad_to.max(1);
+	let len = (data.len() / pad_to + 1) * pad_to;
+
+	let mut out = Vec::with_capacity(len);
+	out.extend_from_slice(data);
+	out.push(0x80);
+	out.resize(len, 0);
+
+	out
+}
+
+fn unpad(data: &[u8]) -> Result<usize, SentcError>
+{
+	let marker = data
+		.iter()
+		.rposition(|b| *b != 0)
+		.ok_or(SentcError::InvalidPadding)?;
+
+	if data[marker] != 0x80 {
+		return Err(SentcError::InvalidPadding);
+	}
+
+	Ok(marker)
+}
+
+#[cfg(feature = "compression")]
+fn compress(data: &[u8]) -> Result<Vec<u8>, SentcError>
+{
+	zstd::bulk::compress(data, ZSTD_LEVEL).map_err(SentcError::CompressionFailed)
+}
+
+#[cfg(not(feature = "compression"))]
+fn compress(_data: &[u8]) -> Result<Vec<u8>, SentcError>
+{
+	Err(SentcError::CompressionNotEnabled)
+}
+
+#[cfg(feature = "compression")]
+fn decompress(data: &[u8]) -> Result<Vec<u8>, SentcError>
+{
+	use std::io::Read;
+
+	let decoder = zstd::stream::Decoder::new(data).map_err(SentcError::CompressionFailed)?;
+
+	//read one byte more than allowed to detect too large data
+	let mut out = Vec::new();
+	decoder
+		.take(MAX_DECOMPRESSED_SIZE + 1)
+		.read_to_end(&mut out)
+		.map_err(SentcError::CompressionFailed)?;
+
+	if out.len() as u64 > MAX_DECOMPRESSED_SIZE {
+		return Err(SentcError::DecompressedDataTooLarge);
+	}
+
+	Ok(out)
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress(_data: &[u8]) -> Result<Vec<u8>, SentcError>
+{
+	Err(SentcError::CompressionNotEnabled)
+}
+
+#[cfg(all(test, feature = "compression"))]
+mod test
+{
+	use super::*;
+
+	#[test]
+	fn test_decompress_with_max_size()
+	{
+		let data = vec![0u8; MAX_DECOMPRESSED_SIZE as usize];
+
+		let out = decompress(&compress(&data).unwrap()).unwrap();
+
+		assert_eq!(out.len(), data.len());
+	}
+
+	#[test]
+	fn test_not_decompress_too_large_data()
+	{
+		let data = vec![0u8; MAX_DECOMPRESSED_SIZE as usize + 1];
+
+		let err = decompress(&compress(&data).unwrap()).unwrap_err();
+
+		assert!(matches!(err, SentcError::DecompressedDataTooLarge));
+	}
+}
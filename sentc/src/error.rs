@@ -1,6 +1,8 @@
 use sentc_crypto::sdk_utils::error::SdkUtilError;
 use sentc_crypto::SdkError;
 
+use crate::group::rank::{GroupAction, GroupRank};
+
 #[derive(Debug)]
 pub enum SentcError
 {
@@ -31,6 +33,26 @@ pub enum SentcError
 
 	GroupFetchUserKeyNotFound,
 	GroupFetchGroupKeyNotFound(String),
+	GroupPermission(GroupRank, GroupAction),
+	GroupOlderKeysNotLoaded,
+	GroupKeyNotSigned(String),
+	GroupKeyVerifyFailed(String),
+	GroupKeyRotationAfterKickFailed(Box<SentcError>),
+	GroupOwnerDemoteFailed(Box<SentcError>),
+
+	UserPublicKeyNotVerified(String),
+
+	InvalidPadding,
+	CompressionNotEnabled,
+	#[cfg(feature = "compression")]
+	CompressionFailed(std::io::Error),
+	#[cfg(feature = "compression")]
+	DecompressedDataTooLarge,
+
+	ReindexInvalidValue(String),
+
+	#[cfg(feature = "sqlite")]
+	Sqlite(rusqlite::Error),
 }
 
 impl From<SdkError> for SentcError
@@ -64,3 +86,12 @@ impl From<SdkUtilError> for SentcError
 		Self::Sdk(SdkError::Util(value))
 	}
 }
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for SentcError
+{
+	fn from(value: rusqlite::Error) -> Self
+	{
+		Self::Sqlite(value)
+	}
+}
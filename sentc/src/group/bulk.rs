@@ -0,0 +1,169 @@
+use std::future::Future;
+
+use futures_util::{stream, StreamExt};
+use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKeyGen};
+use sentc_crypto::sdk_utils::cryptomat::{
+	PkFromUserKeyWrapper,
+	SearchableKeyComposerWrapper,
+	SignComposerWrapper,
+	SignKeyPairWrapper,
+	SortableKeyComposerWrapper,
+	StaticKeyComposerWrapper,
+	StaticKeyPairWrapper,
+	SymKeyComposerWrapper,
+	SymKeyGenWrapper,
+	VerifyKFromUserKeyWrapper,
+};
+
+use crate::crypto_common::UserId;
+use crate::error::SentcError;
+use crate::group::rank::{GroupAction, GroupRank};
+use crate::group::Group;
+use crate::net_helper::check_jwt;
+use crate::user::User;
+
+/// The result of a bulk action for one user.
+///
+/// The results are in the same order as the user ids.
+#[derive(Debug)]
+pub struct GroupBulkResult
+{
+	pub user_id: UserId,
+	pub result: Result<(), SentcError>,
+}
+
+/// The result of a bulk kick with a key rotation afterward.
+#[derive(Debug)]
+pub struct GroupKickAndRotateResult
+{
+	/// The result of the kick for each member.
+	pub kicked: Vec<GroupBulkResult>,
+	/// The result of the key rotation. None if no member was kicked, then the keys are not rotated.
+	///
+	/// The members stay kicked if the rotation failed, start it again with key_rotation.
+	pub rotation: Option<Result<(), SentcError>>,
+}
+
+impl<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+	Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+where
+	SGen: SymKeyGenWrapper,
+	StGen: StaticKeyPairWrapper,
+	SignGen: SignKeyPairWrapper,
+	SearchGen: SearchableKeyGen,
+	SortGen: SortableKeyGen,
+	SC: SymKeyComposerWrapper,
+	StC: StaticKeyComposerWrapper,
+	SignC: SignComposerWrapper,
+	SearchC: SearchableKeyComposerWrapper,
+	SortC: SortableKeyComposerWrapper,
+	PC: PkFromUserKeyWrapper,
+	VC: VerifyKFromUserKeyWrapper,
+	PwH: PwHash,
+{
+	/// Invite many user at once.
+	///
+	/// The public keys of the user are fetched and the invites are sent with max. limit requests at the same time.
+	/// The group keys are only prepared once for all user.
+	///
+	/// Only an error for the whole action (wrong rank, missing keys) is returned as error,
+	/// the result for each user is in the returned vec.
+	pub async fn invite_many(
+		&self,
+		jwt: &str,
+		user_ids: &[&str],
+		rank: Option<GroupRank>,
+		auto: bool,
+		limit: usize,
+	) -> Result<Vec<GroupBulkResult>, SentcError>
+	{
+		check_jwt(jwt)?;
+		self.check_rank(GroupAction::Invite)?;
+		self.check_all_keys_loaded()?;
+
+		let key_pages = self.prepare_group_key_pages();
+		let key_pages = &key_pages;
+
+		Ok(Self::run_bulk(user_ids, limit, |user_id| {
+			async move {
+				let user_key = self.fetch_user_public_key(user_id, false).await?;
+
+				self.invite_user_with_key_pages(jwt, user_id, &user_key, rank, auto, false, false, key_pages)
+					.await
+			}
+		})
+		.await)
+	}
+
+	/// Kick many user at once with max. limit requests at the same time.
+	pub async fn kick_many(&self, jwt: &str, user_ids: &[&str], limit: usize) -> Result<Vec<GroupBulkResult>, SentcError>
+	{
+		check_jwt(jwt)?;
+		self.check_rank(GroupAction::Kick)?;
+
+		Ok(Self::run_bulk(user_ids, limit, |user_id| self.kick_user(jwt, user_id)).await)
+	}
+
+	/// Kick many user and rotate the keys afterward.
+	///
+	/// The keys are only rotated if at least one user was kicked.
+	/// Only an error for the whole action (wrong rank) is returned as error,
+	/// the results of the kicks and of the rotation are in the returned result.
+	#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+	pub async fn kick_many_and_rotate(
+		&mut self,
+		jwt: &str,
+		user_ids: &[&str],
+		limit: usize,
+		sign: bool,
+		user: Option<&User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		parent_group: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+	) -> Result<GroupKickAndRotateResult, SentcError>
+	{
+		self.check_rank(GroupAction::RotateKeys)?;
+
+		let kicked = self.kick_many(jwt, user_ids, limit).await?;
+
+		let rotation = if kicked.iter().any(|r| r.result.is_ok()) {
+			Some(self.key_rotation(jwt, sign, user, parent_group).await)
+		} else {
+			None
+		};
+
+		Ok(GroupKickAndRotateResult {
+			kicked,
+			rotation,
+		})
+	}
+
+	/// Set the same rank for many user with max. limit requests at the same time.
+	pub async fn update_rank_many(&self, jwt: &str, user_ids: &[&str], new_rank: GroupRank, limit: usize)
+		-> Result<Vec<GroupBulkResult>, SentcError>
+	{
+		check_jwt(jwt)?;
+		self.check_rank(GroupAction::UpdateRank)?;
+
+		Ok(Self::run_bulk(user_ids, limit, |user_id| self.update_rank(jwt, user_id, new_rank)).await)
+	}
+
+	async fn run_bulk<'a, F, Fut>(user_ids: &[&'a str], limit: usize, action: F) -> Vec<GroupBulkResult>
+	where
+		F: Fn(&'a str) -> Fut,
+		Fut: Future<Output = Result<(), SentcError>>,
+	{
+		stream::iter(user_ids.iter().copied())
+			.map(|user_id| {
+				let res = action(user_id);
+
+				async move {
+					GroupBulkResult {
+						user_id: user_id.to_string(),
+						result: res.await,
+					}
+				}
+			})
+			.buffered(limit.max(1))
+			.collect()
+			.await
+	}
+}
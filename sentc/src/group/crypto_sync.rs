@@ -16,9 +16,12 @@ use sentc_crypto::sdk_utils::cryptomat::{
 	SymKeyGenWrapper,
 	VerifyKFromUserKeyWrapper,
 };
+use sentc_crypto::sdk_utils::put_head_and_encrypted_data;
 
+use crate::encrypt_options::{apply_options, decode_string, decrypted_to_string, encode_string, undo_options, EncryptOptions, OptionsEncryptedHead};
 use crate::error::SentcError;
 use crate::group::Group;
+use crate::user::crypto_sync::{decrypt_multi_encrypted_key, MultiEncryptedHead};
 
 impl<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
 	Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
@@ -125,9 +128,40 @@ where
 
 	pub fn decrypt_sync(&self, data: &[u8], verify_key: Option<&UserVerifyKeyData>) -> Result<Vec<u8>, SentcError>
 	{
-		let (head, data) = split_head_and_encrypted_data(data)?;
+		let (head, data): (OptionsEncryptedHead, _) = split_head_and_encrypted_data(data)?;
+
+		let decrypted = self.decrypt_raw_sync(&head.head, data, verify_key)?;
+
+		undo_options(decrypted, &head)
+	}
+
+	//______________________________________________________________________________________________
+	//encrypt with options
+
+	/// Compress and/or pad the data before encryption. Decrypt it with decrypt_sync.
+	pub fn encrypt_with_options_sync(&self, data: &[u8], options: &EncryptOptions) -> Result<Vec<u8>, SentcError>
+	{
+		let (head, encrypted) = self.encrypt_raw_sync(&apply_options(data, options)?)?;
+
+		Ok(put_head_and_encrypted_data(
+			&OptionsEncryptedHead::new(head, options),
+			&encrypted,
+		)?)
+	}
+
+	pub fn encrypt_with_options_with_sign_sync(
+		&self,
+		data: &[u8],
+		options: &EncryptOptions,
+		sign_key: &impl SignKWrapper,
+	) -> Result<Vec<u8>, SentcError>
+	{
+		let (head, encrypted) = self.encrypt_raw_with_sign_sync(&apply_options(data, options)?, sign_key)?;
 
-		self.decrypt_raw_sync(&head, data, verify_key)
+		Ok(put_head_and_encrypted_data(
+			&OptionsEncryptedHead::new(head, options),
+			&encrypted,
+		)?)
 	}
 
 	//______________________________________________________________________________________________
@@ -175,13 +209,32 @@ where
 
 	pub fn decrypt_string_sync(&self, data: &str, verify_key: Option<&UserVerifyKeyData>) -> Result<String, SentcError>
 	{
-		let head = split_head_and_encrypted_string(data)?;
+		//decrypt it like the bytes to undo the options if the string was encrypted with options
+		let decrypted = self.decrypt_sync(&decode_string(data)?, verify_key)?;
 
-		let key = self
-			.get_group_key(&head.id)
-			.ok_or(SentcError::KeyRequired(head.id.to_string()))?;
+		decrypted_to_string(decrypted)
+	}
 
-		Ok(key.group_key.decrypt_string(data, verify_key)?)
+	/// Compress and/or pad the string before encryption. Decrypt it with decrypt_string_sync.
+	pub fn encrypt_string_with_options_sync(&self, data: &str, options: &EncryptOptions) -> Result<String, SentcError>
+	{
+		Ok(encode_string(
+			&self.encrypt_with_options_sync(data.as_bytes(), options)?,
+		))
+	}
+
+	pub fn encrypt_string_with_options_with_sign_sync(
+		&self,
+		data: &str,
+		options: &EncryptOptions,
+		sign_key: &impl SignKWrapper,
+	) -> Result<String, SentcError>
+	{
+		Ok(encode_string(&self.encrypt_with_options_with_sign_sync(
+			data.as_bytes(),
+			options,
+			sign_key,
+		)?))
 	}
 
 	//______________________________________________________________________________________________
@@ -216,6 +269,30 @@ where
 			.decrypt_string_with_aad(data, aad, verify_key)?)
 	}
 
+	//______________________________________________________________________________________________
+	//decrypt for multiple users
+
+	/// Decrypt data from encrypt_for_users which was encrypted with a public key of this group.
+	pub fn decrypt_for_users_sync(&self, data: &[u8], verify_key: Option<&UserVerifyKeyData>) -> Result<Vec<u8>, SentcError>
+	{
+		let (head, encrypted): (MultiEncryptedHead, _) = split_head_and_encrypted_data(data)?;
+
+		let (entry, group_key) = head
+			.keys
+			.iter()
+			.find_map(|k| {
+				self.keys
+					.iter()
+					.find(|group_key| group_key.exported_public_key.public_key_id == k.id)
+					.map(|group_key| (k, group_key))
+			})
+			.ok_or(SentcError::KeyNotFound)?;
+
+		let key = decrypt_multi_encrypted_key::<SC>(&group_key.private_group_key, entry)?;
+
+		Ok(key.decrypt(encrypted, verify_key)?)
+	}
+
 	//==============================================================================================
 	//sym key
 
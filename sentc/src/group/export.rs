@@ -40,6 +40,10 @@ pub struct GroupExportData
 	keys: Vec<GroupKeyDataExport>,
 	hmac_keys: Vec<String>,
 	sortable_keys: Vec<String>,
+	#[serde(default)]
+	key_limit: Option<usize>,
+	#[serde(default)]
+	older_keys_missing: bool,
 
 	base_url: String,
 	app_token: String,
@@ -92,6 +96,8 @@ where
 				.into_iter()
 				.map(|k| k.to_string())
 				.collect::<Result<_, SdkUtilError>>()?,
+			key_limit: value.key_limit,
+			older_keys_missing: value.older_keys_missing,
 			base_url: value.base_url,
 			app_token: value.app_token,
 		})
@@ -146,6 +152,8 @@ where
 				.iter()
 				.map(|k| k.to_string_ref())
 				.collect::<Result<_, SdkUtilError>>()?,
+			key_limit: value.key_limit,
+			older_keys_missing: value.older_keys_missing,
 			base_url: value.base_url.clone(),
 			app_token: value.app_token.clone(),
 		})
@@ -215,6 +223,9 @@ where
 			})
 			.collect::<Result<_, SdkUtilError>>()?;
 
+		group.key_limit = self.key_limit;
+		group.older_keys_missing = self.older_keys_missing;
+
 		Ok(group)
 	}
 }
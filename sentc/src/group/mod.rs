@@ -4,14 +4,27 @@
 #![doc=include_str!("../../doc/sortable.md")]
 #![doc=include_str!("../../doc/file.md")]
 
+#[cfg(feature = "network")]
+pub mod bulk;
 pub mod crypto_sync;
 mod export;
 #[cfg(feature = "file")]
 pub mod file;
 #[cfg(feature = "network")]
 pub mod net;
+pub mod rank;
+pub mod reindex;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "network")]
+pub mod tree;
+#[cfg(feature = "watcher")]
+pub mod watcher;
 
+#[cfg(feature = "network")]
+use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::ops::Bound;
 
 use sentc_crypto::entities::group::GroupKeyData;
 use sentc_crypto::group::{prepare_change_rank, Group as SdkGroup};
@@ -41,6 +54,19 @@ use sentc_crypto::sdk_utils::cryptomat::{
 
 use crate::crypto_common::user::UserVerifyKeyData;
 use crate::error::SentcError;
+use crate::group::rank::{GroupAction, GroupRank};
+use crate::searchable::{SearchIndexBuilder, SearchQueryBuilder};
+use crate::sortable::{
+	encrypt_sortable_number_range,
+	encrypt_sortable_raw_value,
+	encrypt_sortable_string_range,
+	encrypt_sortable_value,
+	encrypt_sortable_value_range,
+	SortableRange,
+	SortableValue,
+	SortableValueEncryptOutput,
+	SortableValueRange,
+};
 use crate::user::User;
 use crate::KeyMap;
 
@@ -58,7 +84,7 @@ macro_rules! prepare_group_keys_ref {
 
 		(
 			$keys[offset..end].iter().map(|k| &k.group_key).collect(),
-			end < $keys.len() - 1,
+			end < $keys.len(),
 		)
 	}};
 }
@@ -109,6 +135,12 @@ where
 	sortable_keys: Vec<SortC::SortableKeyWrapper>,
 	newest_key_id: SymKeyId,
 	key_map: KeyMap,
+	#[cfg(feature = "network")]
+	non_registered_keys: HashMap<String, SC::SymmetricKeyWrapper>,
+	/// Only load the newest keys when fetching the group, the older keys are fetched when they are needed.
+	key_limit: Option<usize>,
+	/// True if the group was loaded with a key limit and the older keys are not loaded yet.
+	older_keys_missing: bool,
 
 	base_url: String,
 	app_token: String,
@@ -182,6 +214,10 @@ where
 			sortable_keys: Vec::with_capacity(sort_key_len),
 			newest_key_id: "".to_string(),
 			key_map: Default::default(),
+			#[cfg(feature = "network")]
+			non_registered_keys: Default::default(),
+			key_limit: None,
+			older_keys_missing: false,
 
 			_sgen: Default::default(),
 			_st_gen: Default::default(),
@@ -262,6 +298,42 @@ where
 		self.rank
 	}
 
+	pub fn get_group_rank(&self) -> Result<GroupRank, SentcError>
+	{
+		self.rank.try_into()
+	}
+
+	/// Set the rank of the user in this group, e.g. after an admin changed the rank.
+	///
+	/// No request is sent, use group_update_check to fetch the rank from the server.
+	pub fn update_own_rank(&mut self, new_rank: GroupRank)
+	{
+		self.rank = new_rank.into();
+	}
+
+	/// Check the own rank before doing an action, so the request fails before it gets to the server.
+	pub fn check_rank(&self, action: GroupAction) -> Result<(), SentcError>
+	{
+		self.get_group_rank()?.check(action)
+	}
+
+	pub fn get_key_limit(&self) -> Option<usize>
+	{
+		self.key_limit
+	}
+
+	/// Set the key limit for the child and connected groups which are fetched from this group.
+	pub fn set_key_limit(&mut self, key_limit: Option<usize>)
+	{
+		self.key_limit = key_limit;
+	}
+
+	/// False if the group was loaded with a key limit and the older keys are not fetched yet.
+	pub fn has_all_keys(&self) -> bool
+	{
+		!self.older_keys_missing
+	}
+
 	pub fn get_newest_hmac_key(&self) -> &SearchC::SearchableKeyWrapper
 	{
 		&self.hmac_keys[0]
@@ -291,6 +363,8 @@ where
 
 	pub fn prepare_update_rank(&self, user_id: &str, new_rank: i32) -> Result<String, SentcError>
 	{
+		self.check_rank(GroupAction::UpdateRank)?;
+
 		Ok(prepare_change_rank(user_id, new_rank, self.rank)?)
 	}
 
@@ -327,6 +401,8 @@ where
 		parent_group: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
 	) -> Result<String, SentcError>
 	{
+		self.check_rank(GroupAction::RotateKeys)?;
+
 		let pk = if !self.from_parent && self.access_by_group_as_member.is_none() {
 			user.ok_or(SentcError::UserNotFound)?
 				.get_newest_public_key()
@@ -371,6 +447,8 @@ where
 
 	pub fn prepare_group_keys_for_new_member(&self, user_public_key: &UserPublicKeyData, new_user_rank: Option<i32>) -> Result<String, SentcError>
 	{
+		self.check_all_keys_loaded()?;
+
 		let (keys, _) = self.prepare_group_keys_ref(0);
 
 		let key_session = self.keys.len() > 50;
@@ -457,6 +535,29 @@ where
 		Ok(key.search(data)?)
 	}
 
+	/// Create the searchable hashes of multiple fields with the newest hmac key.
+	pub fn search_index(&self) -> SearchIndexBuilder<'_, SearchC::SearchableKeyWrapper>
+	{
+		SearchIndexBuilder::new(self.get_newest_hmac_key())
+	}
+
+	/// Create the hashes to query the fields of a search index.
+	pub fn search_query(&self) -> SearchQueryBuilder<'_, SearchC::SearchableKeyWrapper>
+	{
+		SearchQueryBuilder::new(self.get_newest_hmac_key())
+	}
+
+	/// Create the search hash with every hmac key, not only with the newest key.
+	///
+	/// Returns the key id and the hash. Use the key id of create_search to match the hashes of the right key.
+	pub fn search_all(&self, data: &str) -> Result<Vec<(SymKeyId, String)>, SentcError>
+	{
+		self.hmac_keys
+			.iter()
+			.map(|key| Ok((key.get_id().to_string(), key.search(data)?)))
+			.collect()
+	}
+
 	//______________________________________________________________________________________________
 	//sortable
 
@@ -488,6 +589,39 @@ where
 		Ok(key.encrypt_string(data, max_len)?)
 	}
 
+	/// Encrypt signed numbers, floats, timestamps or fixed-point numbers.
+	///
+	/// The value is split into multiple numbers. Compare them one after another.
+	pub fn encrypt_sortable_raw_value(&self, value: &impl SortableValue) -> Result<Vec<u64>, SentcError>
+	{
+		encrypt_sortable_raw_value(self.get_newest_sortable_key(), value)
+	}
+
+	pub fn encrypt_sortable_value(&self, value: &impl SortableValue) -> Result<SortableValueEncryptOutput, SentcError>
+	{
+		encrypt_sortable_value(self.get_newest_sortable_key(), value)
+	}
+
+	/// Get the encrypted bounds for a range query of encrypted numbers.
+	pub fn encrypt_sortable_number_range(&self, lower: Bound<u64>, upper: Bound<u64>) -> Result<SortableRange, SentcError>
+	{
+		encrypt_sortable_number_range(self.get_newest_sortable_key(), lower, upper)
+	}
+
+	/// Get the encrypted bounds for a range query of encrypted strings.
+	///
+	/// The range is not exact, filter the values after decryption.
+	pub fn encrypt_sortable_string_range(&self, lower: Bound<&str>, upper: Bound<&str>, max_len: Option<usize>) -> Result<SortableRange, SentcError>
+	{
+		encrypt_sortable_string_range(self.get_newest_sortable_key(), lower, upper, max_len)
+	}
+
+	/// Get the encrypted bounds for a range query of encrypted signed numbers, floats, timestamps or fixed-point numbers.
+	pub fn encrypt_sortable_value_range<V: SortableValue>(&self, lower: Bound<&V>, upper: Bound<&V>) -> Result<SortableValueRange, SentcError>
+	{
+		encrypt_sortable_value_range(self.get_newest_sortable_key(), lower, upper)
+	}
+
 	//==============================================================================================
 	//internal fn
 
@@ -496,6 +630,28 @@ where
 		prepare_group_keys_ref!(self.keys, page, 50)
 	}
 
+	/// All pages of the group keys, to use the same pages for many user.
+	#[cfg(feature = "network")]
+	pub(crate) fn prepare_group_key_pages(&self) -> Vec<Vec<&SC::SymmetricKeyWrapper>>
+	{
+		let mut pages = Vec::with_capacity(self.keys.len() / 50 + 1);
+		let mut page = 0;
+
+		loop {
+			let (keys, next_page) = self.prepare_group_keys_ref(page);
+
+			pages.push(keys);
+
+			if !next_page {
+				break;
+			}
+
+			page += 1;
+		}
+
+		pages
+	}
+
 	#[allow(clippy::type_complexity)]
 	pub fn get_last_key(&self) -> Result<&GroupKeyData<SC::SymmetricKeyWrapper, StC::SkWrapper, StC::PkWrapper>, SentcError>
 	{
@@ -503,6 +659,16 @@ where
 		self.keys.last().ok_or(SentcError::KeyNotFound)
 	}
 
+	/// New members need all keys of the group, not only the newest.
+	pub(crate) fn check_all_keys_loaded(&self) -> Result<(), SentcError>
+	{
+		if self.older_keys_missing {
+			return Err(SentcError::GroupOlderKeysNotLoaded);
+		}
+
+		Ok(())
+	}
+
 	pub(crate) fn set_newest_key_id(&mut self, id: SymKeyId)
 	{
 		self.newest_key_id = id;
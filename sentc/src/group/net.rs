@@ -1,8 +1,11 @@
+use std::collections::HashMap;
 use std::future::Future;
 
+use futures_util::Stream;
+use sentc_crypto::crypto::KeyGenerator;
 use sentc_crypto::entities::group::GroupOutData;
 use sentc_crypto::group::Group as SdkGroup;
-use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKeyGen};
+use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKeyGen, SymKey};
 use sentc_crypto::sdk_utils::cryptomat::{
 	PkFromUserKeyWrapper,
 	SearchableKeyComposerWrapper,
@@ -13,8 +16,11 @@ use sentc_crypto::sdk_utils::cryptomat::{
 	StaticKeyPairWrapper,
 	SymKeyComposerWrapper,
 	SymKeyGenWrapper,
+	SymKeyWrapper,
 	VerifyKFromUserKeyWrapper,
 };
+use sentc_crypto::sdk_utils::handle_general_server_response;
+use sentc_crypto::sdk_utils::http::{make_req, HttpMethod};
 use sentc_crypto::util_req_full::group::{
 	accept_invite,
 	delete_group,
@@ -39,7 +45,9 @@ use sentc_crypto::util_req_full::group::{
 	update_rank,
 };
 
+use crate::crypto_common::crypto::GeneratedSymKeyHeadServerOutput;
 use crate::crypto_common::group::{
+	GroupChangeRankServerInput,
 	GroupChildrenList,
 	GroupHmacData,
 	GroupInviteReqList,
@@ -51,10 +59,11 @@ use crate::crypto_common::group::{
 	ListGroups,
 };
 use crate::crypto_common::user::{UserPublicKeyData, UserVerifyKeyData};
-use crate::crypto_common::SymKeyId;
+use crate::crypto_common::{SignKeyPairId, SymKeyId, UserId};
 use crate::error::SentcError;
+use crate::group::rank::{GroupAction, GroupRank};
 use crate::group::{Group, GroupKeyVerifyKeys};
-use crate::net_helper::check_jwt;
+use crate::net_helper::{check_jwt, get_group_public_key, get_user_public_key_data, get_user_verify_key_data, list_stream};
 use crate::user::User;
 
 #[derive(Debug)]
@@ -65,6 +74,42 @@ pub enum GroupFetchResult
 	MissingGroupKeys(Vec<String>),
 }
 
+impl GroupFetchResult
+{
+	/// Missing keys as error for the fetch functions which do everything in one call.
+	pub(crate) fn into_result(self) -> Result<(), SentcError>
+	{
+		match self {
+			Self::Ok => Ok(()),
+			Self::MissingUserKeys(_) => Err(SentcError::GroupFetchUserKeyNotFound),
+			Self::MissingGroupKeys(keys) => {
+				Err(SentcError::GroupFetchGroupKeyNotFound(
+					keys.into_iter().next().unwrap_or_default(),
+				))
+			},
+		}
+	}
+}
+
+/// What to do when a group key is not signed or the signature is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupKeyVerifyMode
+{
+	/// Return an error.
+	Fail,
+	/// Load the key anyway and return a warning for the key.
+	Warn,
+}
+
+#[derive(Debug)]
+pub enum GroupKeyVerifyWarning
+{
+	NotSigned(SymKeyId),
+	InvalidSignature(SymKeyId),
+	/// The verify key of the signer can't be fetched, the key is loaded without verification.
+	VerifyKeyNotFound(SymKeyId),
+}
+
 pub enum GroupKeyFetchResult
 {
 	Done,
@@ -125,6 +170,18 @@ where
 		.await?)
 	}
 
+	/// Like get_children but fetches all pages.
+	pub fn get_children_stream<'a>(&'a self, jwt: &'a str) -> impl Stream<Item = Result<GroupChildrenList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_children(jwt, pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn prepare_get_child_group(&self, group_id: &str, jwt: &str) -> Result<(GroupOutData, GroupFetchResult), SentcError>
 	{
 		check_jwt(jwt)?;
@@ -138,6 +195,7 @@ where
 			None,
 			Some(self),
 			true,
+			self.key_limit,
 		)
 		.await
 	}
@@ -157,9 +215,56 @@ where
 			None,
 			Some(self),
 			verify_keys,
+			self.key_limit,
 		)
 	}
 
+	/// Like done_get_child_group but every signed group key is verified with the verify key of the signer.
+	///
+	/// The verify keys are fetched from the server.
+	#[allow(clippy::type_complexity)]
+	pub async fn done_get_child_group_verified(
+		&self,
+		data: GroupOutData,
+		mode: GroupKeyVerifyMode,
+	) -> Result<
+		(
+			Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+			Vec<GroupKeyVerifyWarning>,
+		),
+		SentcError,
+	>
+	{
+		Group::<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>::done_fetch_group_verified(
+			self.base_url.clone(),
+			self.app_token.clone(),
+			true,
+			data,
+			None,
+			Some(self),
+			self.key_limit,
+			mode,
+		)
+		.await
+	}
+
+	/// Fetch a child group in one call.
+	///
+	/// Returns an error if a key of this group to decrypt the child group keys is not loaded.
+	#[allow(clippy::type_complexity)]
+	pub async fn get_child_group(
+		&self,
+		group_id: &str,
+		jwt: &str,
+	) -> Result<Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>, SentcError>
+	{
+		let (data, res) = self.prepare_get_child_group(group_id, jwt).await?;
+
+		res.into_result()?;
+
+		self.done_get_child_group(data, None)
+	}
+
 	pub async fn prepare_get_connected_group(&self, group_id: &str, jwt: &str) -> Result<(GroupOutData, GroupFetchResult), SentcError>
 	{
 		check_jwt(jwt)?;
@@ -173,6 +278,7 @@ where
 			None,
 			Some(self),
 			false,
+			self.key_limit,
 		)
 		.await
 	}
@@ -192,12 +298,60 @@ where
 			None,
 			Some(self),
 			verify_keys,
+			self.key_limit,
+		)
+	}
+
+	/// Like done_get_connected_group but every signed group key is verified with the verify key of the signer.
+	///
+	/// The verify keys are fetched from the server.
+	#[allow(clippy::type_complexity)]
+	pub async fn done_get_connected_group_verified(
+		&self,
+		data: GroupOutData,
+		mode: GroupKeyVerifyMode,
+	) -> Result<
+		(
+			Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+			Vec<GroupKeyVerifyWarning>,
+		),
+		SentcError,
+	>
+	{
+		Group::<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>::done_fetch_group_verified(
+			self.base_url.clone(),
+			self.app_token.clone(),
+			false,
+			data,
+			None,
+			Some(self),
+			self.key_limit,
+			mode,
 		)
+		.await
+	}
+
+	/// Fetch a connected group in one call.
+	///
+	/// Returns an error if a key of this group to decrypt the connected group keys is not loaded.
+	#[allow(clippy::type_complexity)]
+	pub async fn get_connected_group(
+		&self,
+		group_id: &str,
+		jwt: &str,
+	) -> Result<Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>, SentcError>
+	{
+		let (data, res) = self.prepare_get_connected_group(group_id, jwt).await?;
+
+		res.into_result()?;
+
+		self.done_get_connected_group(data, None)
 	}
 
 	pub async fn create_child_group(&self, jwt: &str) -> Result<String, SentcError>
 	{
 		check_jwt(jwt)?;
+		self.check_rank(GroupAction::CreateChildGroup)?;
 
 		let last_key = &self
 			.get_newest_key()
@@ -228,6 +382,7 @@ where
 	) -> Result<String, SentcError>
 	{
 		check_jwt(jwt)?;
+		self.check_rank(GroupAction::CreateChildGroup)?;
 
 		let last_key = &self
 			.get_newest_key()
@@ -258,6 +413,7 @@ where
 	pub async fn create_connected_group(&self, jwt: &str) -> Result<String, SentcError>
 	{
 		check_jwt(jwt)?;
+		self.check_rank(GroupAction::CreateChildGroup)?;
 
 		let last_key = &self
 			.get_newest_key()
@@ -288,6 +444,7 @@ where
 	) -> Result<String, SentcError>
 	{
 		check_jwt(jwt)?;
+		self.check_rank(GroupAction::CreateChildGroup)?;
 
 		let last_key = &self
 			.get_newest_key()
@@ -417,6 +574,135 @@ where
 		Ok(())
 	}
 
+	/// Fetch and decrypt a group key which is not loaded yet, e.g. an older key of a group which was loaded with a key limit.
+	///
+	/// Returns Done if the key is loaded. If the private key to decrypt the group key is missing,
+	/// the missing key is returned like in prepare_fetch_group_key.
+	#[allow(clippy::type_complexity)]
+	pub async fn load_group_key(
+		&mut self,
+		group_key_id: &str,
+		jwt: &str,
+		user: Option<&User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		parent_group: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		verify_key: Option<&UserVerifyKeyData>,
+	) -> Result<GroupKeyFetchResult, SentcError>
+	{
+		match self
+			.prepare_fetch_group_key(group_key_id, jwt, user, parent_group)
+			.await?
+		{
+			GroupKeyFetchResult::Ok(data) => {
+				self.decrypt_group_keys(user, parent_group, data, verify_key)?;
+
+				Ok(GroupKeyFetchResult::Done)
+			},
+			res => Ok(res),
+		}
+	}
+
+	/// Fetch all keys of the group which are not loaded because of the key limit.
+	///
+	/// All keys are needed before inviting a new member or accepting a join request.
+	/// If private keys are missing to decrypt the group keys, nothing is loaded and the missing keys are returned.
+	#[allow(clippy::type_complexity)]
+	pub async fn load_older_keys(
+		&mut self,
+		jwt: &str,
+		user: Option<&User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		parent_group: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+	) -> Result<GroupFetchResult, SentcError>
+	{
+		check_jwt(jwt)?;
+
+		if !self.older_keys_missing {
+			return Ok(GroupFetchResult::Ok);
+		}
+
+		let keys: Vec<GroupKeyServerOutput> = fetch_keys(
+			self.base_url.clone(),
+			&self.app_token,
+			jwt,
+			self.get_group_id(),
+			self.get_access_group_as_member(),
+			0,
+			"none".to_string(),
+			None,
+		)
+		.await?
+		.into_iter()
+		.filter(|k| self.has_group_key(&k.group_key_id).is_none())
+		.collect();
+
+		let res = Self::check_missing_private_keys(
+			&keys,
+			self.from_parent || self.access_by_group_as_member.is_some(),
+			user,
+			parent_group,
+		)?;
+
+		if !matches!(res, GroupFetchResult::Ok) {
+			return Ok(res);
+		}
+
+		for key in keys {
+			self.decrypt_group_keys(user, parent_group, key, None)?;
+		}
+
+		self.older_keys_missing = false;
+
+		Ok(GroupFetchResult::Ok)
+	}
+
+	/// Get a non-registered key from its encrypted key blob (the exported GeneratedSymKeyHeadServerOutput).
+	///
+	/// If the group key which encrypted the key is not loaded yet, it will be fetched first.
+	/// Decrypted keys are cached for this group instance,
+	/// so using the same encrypted key blob again will not decrypt it again.
+	#[allow(clippy::type_complexity)]
+	pub async fn get_non_registered_key(
+		&mut self,
+		jwt: &str,
+		server_output: &str,
+		user: Option<&User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		parent_group: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+	) -> Result<&SC::SymmetricKeyWrapper, SentcError>
+	{
+		let out = GeneratedSymKeyHeadServerOutput::from_string(server_output)?;
+
+		//non registered keys got all the same key id, use the encrypted key instead
+		if self
+			.non_registered_keys
+			.contains_key(&out.encrypted_key_string)
+		{
+			return self
+				.non_registered_keys
+				.get(&out.encrypted_key_string)
+				.ok_or(SentcError::KeyNotFound);
+		}
+
+		match self
+			.prepare_fetch_group_key(&out.master_key_id, jwt, user, parent_group)
+			.await?
+		{
+			GroupKeyFetchResult::Done => {},
+			GroupKeyFetchResult::Ok(data) => self.done_fetch_group_key(data, user, parent_group, None)?,
+			GroupKeyFetchResult::MissingGroupKey((key_id, _)) => return Err(SentcError::GroupFetchGroupKeyNotFound(key_id)),
+			GroupKeyFetchResult::MissingUserKeys(_) => return Err(SentcError::GroupFetchUserKeyNotFound),
+		}
+
+		let master_key = self
+			.get_group_key(&out.master_key_id)
+			.ok_or(SentcError::KeyRequired(out.master_key_id.clone()))?;
+
+		let key = KeyGenerator::<SGen, SC, PC>::decrypt_sym_key(&master_key.group_key, &out)?;
+
+		Ok(self
+			.non_registered_keys
+			.entry(out.encrypted_key_string)
+			.or_insert(key))
+	}
+
 	#[allow(clippy::type_complexity)]
 	pub async fn prepare_key_rotation(
 		&self,
@@ -427,6 +713,7 @@ where
 	) -> Result<GroupKeyFetchResult, SentcError>
 	{
 		check_jwt(jwt)?;
+		self.check_rank(GroupAction::RotateKeys)?;
 
 		let pk = if !self.from_parent && self.access_by_group_as_member.is_none() {
 			user.ok_or(SentcError::UserNotFound)?
@@ -470,6 +757,29 @@ where
 			.await
 	}
 
+	/// Start a key rotation and load the new key into this group.
+	///
+	/// The other members get the new key after finishing the rotation.
+	#[allow(clippy::type_complexity)]
+	pub async fn key_rotation(
+		&mut self,
+		jwt: &str,
+		sign: bool,
+		user: Option<&User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		parent_group: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+	) -> Result<(), SentcError>
+	{
+		match self
+			.prepare_key_rotation(jwt, sign, user, parent_group)
+			.await?
+		{
+			GroupKeyFetchResult::Done => Ok(()),
+			GroupKeyFetchResult::Ok(data) => self.done_fetch_group_key_after_rotation(data, user, parent_group, None),
+			GroupKeyFetchResult::MissingGroupKey((key_id, _)) => Err(SentcError::GroupFetchGroupKeyNotFound(key_id)),
+			GroupKeyFetchResult::MissingUserKeys(_) => Err(SentcError::GroupFetchUserKeyNotFound),
+		}
+	}
+
 	#[allow(clippy::type_complexity)]
 	pub async fn prepare_finish_key_rotation(
 		&self,
@@ -617,12 +927,76 @@ where
 		Ok(fetch_results)
 	}
 
+	/// Finish all open key rotations of the group and load the new keys.
+	///
+	/// Older group keys which are required for the rotation are loaded too.
+	#[allow(clippy::type_complexity)]
+	pub async fn finish_key_rotation(
+		&mut self,
+		jwt: &str,
+		user: Option<&User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		parent_group: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+	) -> Result<(), SentcError>
+	{
+		let keys = match self
+			.prepare_finish_key_rotation(jwt, user, parent_group)
+			.await?
+		{
+			GroupFinishKeyRotation::Empty => return Ok(()),
+			GroupFinishKeyRotation::Ok(keys) => keys,
+			GroupFinishKeyRotation::MissingKeys {
+				rotation,
+				group_keys,
+				group_private_keys,
+				user_private_keys,
+			} => {
+				if !user_private_keys.is_empty() {
+					return Err(SentcError::GroupFetchUserKeyNotFound);
+				}
+
+				if let Some(key_id) = group_private_keys.into_iter().next() {
+					return Err(SentcError::GroupFetchGroupKeyNotFound(key_id));
+				}
+
+				for key_id in group_keys {
+					match self
+						.load_group_key(&key_id, jwt, user, parent_group, None)
+						.await?
+					{
+						GroupKeyFetchResult::MissingGroupKey((key_id, _)) => return Err(SentcError::GroupFetchGroupKeyNotFound(key_id)),
+						GroupKeyFetchResult::MissingUserKeys(_) => return Err(SentcError::GroupFetchUserKeyNotFound),
+						_ => {},
+					}
+				}
+
+				rotation
+			},
+		};
+
+		for res in self
+			.done_key_rotation(jwt, keys, user, parent_group)
+			.await?
+		{
+			match res {
+				GroupKeyFetchResult::Done => {},
+				GroupKeyFetchResult::Ok(data) => self.done_fetch_group_key_after_rotation(data, user, parent_group, None)?,
+				GroupKeyFetchResult::MissingGroupKey((key_id, _)) => return Err(SentcError::GroupFetchGroupKeyNotFound(key_id)),
+				GroupKeyFetchResult::MissingUserKeys(_) => return Err(SentcError::GroupFetchUserKeyNotFound),
+			}
+		}
+
+		self.key_update = false;
+
+		Ok(())
+	}
+
 	//______________________________________________________________________________________________
 	//admin fn for user management
 
-	pub async fn update_rank(&self, jwt: &str, user_id: &str, new_rank: i32) -> Result<(), SentcError>
+	pub async fn update_rank(&self, jwt: &str, user_id: &str, new_rank: GroupRank) -> Result<(), SentcError>
 	{
 		check_jwt(jwt)?;
+		self.check_rank(GroupAction::UpdateRank)?;
 
 		Ok(update_rank(
 			self.base_url.clone(),
@@ -630,7 +1004,7 @@ where
 			jwt,
 			self.get_group_id(),
 			user_id,
-			new_rank,
+			new_rank.as_i32(),
 			self.rank,
 			self.access_by_group_as_member.as_deref(),
 		)
@@ -640,6 +1014,7 @@ where
 	pub async fn kick_user(&self, jwt: &str, user_id: &str) -> Result<(), SentcError>
 	{
 		check_jwt(jwt)?;
+		self.check_rank(GroupAction::Kick)?;
 
 		Ok(kick_user(
 			self.base_url.clone(),
@@ -653,6 +1028,73 @@ where
 		.await?)
 	}
 
+	/// Kick a member and rotate the keys afterward,
+	/// so the member can't decrypt new content even with a copy of the old keys.
+	///
+	/// Both actions need the rank for it, otherwise the member is not kicked.
+	///
+	/// An error of the kick is returned as it is. If only the rotation failed,
+	/// SentcError::GroupKeyRotationAfterKickFailed is returned, then the member is kicked and the rotation can be started again with key_rotation.
+	#[allow(clippy::type_complexity)]
+	pub async fn kick_user_and_rotate(
+		&mut self,
+		jwt: &str,
+		user_id: &str,
+		sign: bool,
+		user: Option<&User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		parent_group: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+	) -> Result<(), SentcError>
+	{
+		self.check_rank(GroupAction::RotateKeys)?;
+
+		self.kick_user(jwt, user_id).await?;
+
+		self.key_rotation(jwt, sign, user, parent_group)
+			.await
+			.map_err(|e| SentcError::GroupKeyRotationAfterKickFailed(Box::new(e)))
+	}
+
+	/// Hand the group over to another member, e.g. before the creator leaves the group.
+	///
+	/// The new owner gets the creator rank, then the own rank is changed to admin.
+	/// own_id is the user id of this member (or the id of the group for a connected group).
+	///
+	/// If only the demotion failed, both are creators and SentcError::GroupOwnerDemoteFailed is returned.
+	/// Change the own rank again with update_rank.
+	pub async fn transfer_ownership(&mut self, jwt: &str, own_id: &str, new_owner_id: &str) -> Result<(), SentcError>
+	{
+		check_jwt(jwt)?;
+		self.check_rank(GroupAction::TransferOwnership)?;
+
+		//the sdk only allows the ranks 1 to 4 for a rank change, so the request for the creator rank is done here
+		let url = self.base_url.clone() + "/api/v1/group/" + self.get_group_id() + "/change_rank";
+
+		let input = serde_json::to_string(&GroupChangeRankServerInput {
+			changed_user_id: new_owner_id.to_string(),
+			new_rank: GroupRank::Creator.as_i32(),
+		})?;
+
+		let res = make_req(
+			HttpMethod::PUT,
+			&url,
+			&self.app_token,
+			Some(input),
+			Some(jwt),
+			self.access_by_group_as_member.as_deref(),
+		)
+		.await?;
+
+		handle_general_server_response(&res)?;
+
+		self.update_rank(jwt, own_id, GroupRank::Admin)
+			.await
+			.map_err(|e| SentcError::GroupOwnerDemoteFailed(Box::new(e)))?;
+
+		self.update_own_rank(GroupRank::Admin);
+
+		Ok(())
+	}
+
 	//______________________________________________________________________________________________
 
 	pub async fn leave(&self, jwt: &str) -> Result<(), SentcError>
@@ -693,6 +1135,18 @@ where
 		.await?)
 	}
 
+	/// Like get_member but fetches all pages.
+	pub fn get_member_stream<'a>(&'a self, jwt: &'a str) -> impl Stream<Item = Result<GroupUserListItem, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_member(jwt, pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	//______________________________________________________________________________________________
 	//group as member
 
@@ -717,6 +1171,18 @@ where
 		.await?)
 	}
 
+	/// Like get_groups but fetches all pages.
+	pub fn get_groups_stream<'a>(&'a self, jwt: &'a str) -> impl Stream<Item = Result<ListGroups, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_groups(jwt, pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn get_group_invites(&self, jwt: &str, last_item: Option<&GroupInviteReqList>) -> Result<Vec<GroupInviteReqList>, SentcError>
 	{
 		check_jwt(jwt)?;
@@ -739,6 +1205,18 @@ where
 		.await?)
 	}
 
+	/// Like get_group_invites but fetches all pages.
+	pub fn get_group_invites_stream<'a>(&'a self, jwt: &'a str) -> impl Stream<Item = Result<GroupInviteReqList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_group_invites(jwt, pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn accept_group_invite(&self, jwt: &str, group_id_to_accept: &str) -> Result<(), SentcError>
 	{
 		check_jwt(jwt)?;
@@ -792,6 +1270,7 @@ where
 	) -> Result<Vec<GroupInviteReqList>, SentcError>
 	{
 		check_jwt(jwt)?;
+		self.check_rank(GroupAction::GetSentJoinRequests)?;
 
 		let (last_time, last_id) = if let Some(li) = last_fetched_item {
 			(li.time, li.group_id.as_str())
@@ -812,9 +1291,22 @@ where
 		.await?)
 	}
 
+	/// Like get_group_sent_join_req but fetches all pages.
+	pub fn get_group_sent_join_req_stream<'a>(&'a self, jwt: &'a str) -> impl Stream<Item = Result<GroupInviteReqList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_group_sent_join_req(jwt, pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn delete_join_req(&self, id: &str, jwt: &str) -> Result<(), SentcError>
 	{
 		check_jwt(jwt)?;
+		self.check_rank(GroupAction::GetSentJoinRequests)?;
 
 		Ok(delete_sent_join_req(
 			self.base_url.clone(),
@@ -834,6 +1326,7 @@ where
 	pub async fn stop_invites(&self, jwt: &str) -> Result<(), SentcError>
 	{
 		check_jwt(jwt)?;
+		self.check_rank(GroupAction::StopInvites)?;
 
 		Ok(stop_group_invites(
 			self.base_url.clone(),
@@ -851,7 +1344,7 @@ where
 		jwt: &'a str,
 		user_id: &'a str,
 		user_key: &'a UserPublicKeyData,
-		rank: Option<i32>,
+		rank: Option<GroupRank>,
 	) -> impl Future<Output = Result<(), SentcError>> + 'a
 	{
 		self.invite_user_internally(jwt, user_id, user_key, rank, false, false, false)
@@ -862,7 +1355,7 @@ where
 		jwt: &'a str,
 		user_id: &'a str,
 		user_key: &'a UserPublicKeyData,
-		rank: Option<i32>,
+		rank: Option<GroupRank>,
 	) -> impl Future<Output = Result<(), SentcError>> + 'a
 	{
 		self.invite_user_internally(jwt, user_id, user_key, rank, true, false, false)
@@ -873,7 +1366,7 @@ where
 		jwt: &'a str,
 		user_id: &'a str,
 		user_key: &'a UserPublicKeyData,
-		rank: Option<i32>,
+		rank: Option<GroupRank>,
 	) -> impl Future<Output = Result<(), SentcError>> + 'a
 	{
 		self.invite_user_internally(jwt, user_id, user_key, rank, false, true, false)
@@ -884,7 +1377,7 @@ where
 		jwt: &'a str,
 		user_id: &'a str,
 		user_key: &'a UserPublicKeyData,
-		rank: Option<i32>,
+		rank: Option<GroupRank>,
 	) -> impl Future<Output = Result<(), SentcError>> + 'a
 	{
 		self.invite_user_internally(jwt, user_id, user_key, rank, true, true, false)
@@ -910,19 +1403,95 @@ where
 		self.invite_user_internally(jwt, user_id, user_key, None, false, true, true)
 	}
 
+	/// Invite a user without fetching the public key before.
+	///
+	/// If verify is true, the public key must be signed by the user, otherwise the invite is not sent.
+	pub async fn invite_user_by_id(&self, jwt: &str, user_id: &str, rank: Option<GroupRank>, auto: bool, verify: bool) -> Result<(), SentcError>
+	{
+		let user_key = self.fetch_user_public_key(user_id, verify).await?;
+
+		self.invite_user_internally(jwt, user_id, &user_key, rank, auto, false, false)
+			.await
+	}
+
+	/// Invite a group as member without fetching the public key of the group before.
+	pub async fn invite_group_by_id(&self, jwt: &str, group_id: &str, rank: Option<GroupRank>, auto: bool) -> Result<(), SentcError>
+	{
+		let group_key = get_group_public_key(self.base_url.clone(), &self.app_token, group_id).await?;
+
+		self.invite_user_internally(jwt, group_id, &group_key, rank, auto, true, false)
+			.await
+	}
+
+	pub(crate) async fn fetch_user_public_key(&self, user_id: &str, verify: bool) -> Result<UserPublicKeyData, SentcError>
+	{
+		let user_key = get_user_public_key_data(self.base_url.clone(), &self.app_token, user_id).await?;
+
+		if verify {
+			self.check_user_public_key(user_id, &user_key).await?;
+		}
+
+		Ok(user_key)
+	}
+
+	/// Check if the public key is signed by the user, like the verify option of invite_user_by_id.
+	///
+	/// Returns SentcError::UserPublicKeyNotVerified if the key is not signed or the signature is invalid.
+	pub async fn check_user_public_key(&self, user_id: &str, user_key: &UserPublicKeyData) -> Result<(), SentcError>
+	{
+		let verified = User::<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>::verify_user_public_key(
+			self.base_url.clone(),
+			&self.app_token,
+			user_id,
+			user_key,
+		)
+		.await?;
+
+		if !verified {
+			return Err(SentcError::UserPublicKeyNotVerified(user_id.to_string()));
+		}
+
+		Ok(())
+	}
+
 	#[allow(clippy::too_many_arguments)]
 	async fn invite_user_internally(
 		&self,
 		jwt: &str,
 		user_id: &str,
 		user_key: &UserPublicKeyData,
-		rank: Option<i32>,
+		rank: Option<GroupRank>,
 		auto: bool,
 		group: bool,
 		re_invite: bool,
 	) -> Result<(), SentcError>
 	{
-		let (keys, _) = self.prepare_group_keys_ref(0);
+		self.check_rank(GroupAction::Invite)?;
+		self.check_all_keys_loaded()?;
+
+		let key_pages = self.prepare_group_key_pages();
+
+		self.invite_user_with_key_pages(jwt, user_id, user_key, rank, auto, group, re_invite, &key_pages)
+			.await
+	}
+
+	/// Invite a user with already prepared group key pages.
+	///
+	/// The pages can be reused for many user, the rank and the keys must be checked before.
+	#[allow(clippy::too_many_arguments)]
+	pub(crate) async fn invite_user_with_key_pages(
+		&self,
+		jwt: &str,
+		user_id: &str,
+		user_key: &UserPublicKeyData,
+		rank: Option<GroupRank>,
+		auto: bool,
+		group: bool,
+		re_invite: bool,
+		key_pages: &[Vec<&SC::SymmetricKeyWrapper>],
+	) -> Result<(), SentcError>
+	{
+		let keys = key_pages.first().map(Vec::as_slice).unwrap_or_default();
 
 		let session_id = SdkGroup::<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC>::invite_user(
 			self.base_url.clone(),
@@ -931,13 +1500,13 @@ where
 			self.get_group_id(),
 			user_id,
 			self.keys.len() as i32,
-			rank,
+			rank.map(i32::from),
 			self.rank,
 			auto,
 			group,
 			re_invite,
 			user_key,
-			&keys,
+			keys,
 			self.access_by_group_as_member.as_deref(),
 		)
 		.await?;
@@ -948,31 +1517,8 @@ where
 			return Ok(());
 		};
 
-		let mut i = 1;
-		loop {
-			let (next_keys, next_page) = self.prepare_group_keys_ref(i);
-
-			SdkGroup::<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC>::invite_user_session(
-				self.base_url.clone(),
-				&self.app_token,
-				jwt,
-				self.get_group_id(),
-				&session_id,
-				auto,
-				user_key,
-				&next_keys,
-				self.access_by_group_as_member.as_deref(),
-			)
-			.await?;
-
-			if !next_page {
-				break;
-			}
-
-			i += 1;
-		}
-
-		Ok(())
+		self.insert_invite_session_keys(jwt, user_key, &session_id, auto, key_pages)
+			.await
 	}
 
 	pub async fn handle_invite_session_keys_for_new_member(
@@ -983,28 +1529,37 @@ where
 		auto: bool,
 	) -> Result<(), SentcError>
 	{
-		let mut i = 1;
-		loop {
-			let (next_keys, next_page) = self.prepare_group_keys_ref(i);
+		self.check_all_keys_loaded()?;
+
+		let key_pages = self.prepare_group_key_pages();
+
+		self.insert_invite_session_keys(jwt, user_key, &session_id, auto, &key_pages)
+			.await
+	}
 
+	async fn insert_invite_session_keys(
+		&self,
+		jwt: &str,
+		user_key: &UserPublicKeyData,
+		session_id: &str,
+		auto: bool,
+		key_pages: &[Vec<&SC::SymmetricKeyWrapper>],
+	) -> Result<(), SentcError>
+	{
+		//the first page was already sent with the invite
+		for next_keys in key_pages.iter().skip(1) {
 			SdkGroup::<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC>::invite_user_session(
 				self.base_url.clone(),
 				&self.app_token,
 				jwt,
 				self.get_group_id(),
-				&session_id,
+				session_id,
 				auto,
 				user_key,
-				&next_keys,
+				next_keys,
 				self.access_by_group_as_member.as_deref(),
 			)
 			.await?;
-
-			if !next_page {
-				break;
-			}
-
-			i += 1;
 		}
 
 		Ok(())
@@ -1015,6 +1570,8 @@ where
 
 	pub async fn get_join_requests(&self, jwt: &str, last_item: Option<&GroupJoinReqList>) -> Result<Vec<GroupJoinReqList>, SentcError>
 	{
+		self.check_rank(GroupAction::ManageJoinRequests)?;
+
 		let (last_time, last_id) = if let Some(li) = last_item {
 			(li.time, li.user_id.as_str())
 		} else {
@@ -1034,8 +1591,22 @@ where
 		.await?)
 	}
 
+	/// Like get_join_requests but fetches all pages.
+	pub fn get_join_requests_stream<'a>(&'a self, jwt: &'a str) -> impl Stream<Item = Result<GroupJoinReqList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_join_requests(jwt, pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn reject_join_request(&self, jwt: &str, id_to_reject: &str) -> Result<(), SentcError>
 	{
+		self.check_rank(GroupAction::ManageJoinRequests)?;
+
 		Ok(reject_join_req(
 			self.base_url.clone(),
 			&self.app_token,
@@ -1048,8 +1619,12 @@ where
 		.await?)
 	}
 
-	pub async fn accept_join_request(&self, jwt: &str, user_key: &UserPublicKeyData, user_id: &str, rank: Option<i32>) -> Result<(), SentcError>
+	pub async fn accept_join_request(&self, jwt: &str, user_key: &UserPublicKeyData, user_id: &str, rank: Option<GroupRank>)
+		-> Result<(), SentcError>
 	{
+		self.check_rank(GroupAction::ManageJoinRequests)?;
+		self.check_all_keys_loaded()?;
+
 		let (keys, _) = self.prepare_group_keys_ref(0);
 
 		let session_id = SdkGroup::<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC>::accept_join_req(
@@ -1059,7 +1634,7 @@ where
 			self.get_group_id(),
 			user_id,
 			self.keys.len() as i32,
-			rank,
+			rank.map(i32::from),
 			self.rank,
 			user_key,
 			&keys,
@@ -1100,10 +1675,33 @@ where
 		Ok(())
 	}
 
+	/// Accept a join request without fetching the public key of the user before.
+	///
+	/// If verify is true, the public key must be signed by the user, otherwise the request is not accepted.
+	/// For the join request of a group (user_type 2 in the join request list) use accept_group_join_request_by_id.
+	pub async fn accept_join_request_by_id(&self, jwt: &str, user_id: &str, rank: Option<GroupRank>, verify: bool) -> Result<(), SentcError>
+	{
+		let user_key = self.fetch_user_public_key(user_id, verify).await?;
+
+		self.accept_join_request(jwt, &user_key, user_id, rank)
+			.await
+	}
+
+	/// Accept the join request of a group without fetching the public key of the group before.
+	pub async fn accept_group_join_request_by_id(&self, jwt: &str, group_id: &str, rank: Option<GroupRank>) -> Result<(), SentcError>
+	{
+		let group_key = get_group_public_key(self.base_url.clone(), &self.app_token, group_id).await?;
+
+		self.accept_join_request(jwt, &group_key, group_id, rank)
+			.await
+	}
+
 	//______________________________________________________________________________________________
 
 	pub async fn delete_group(&self, jwt: &str) -> Result<(), SentcError>
 	{
+		self.check_rank(GroupAction::DeleteGroup)?;
+
 		Ok(delete_group(
 			self.base_url.clone(),
 			&self.app_token,
@@ -1128,6 +1726,7 @@ where
 		user: Option<&User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
 		parent_group: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
 		parent: bool,
+		key_limit: Option<usize>,
 	) -> Result<(GroupOutData, GroupFetchResult), SentcError>
 	{
 		let mut out = get_group(base_url.clone(), &app_token, jwt, group_id, group_as_member).await?;
@@ -1139,70 +1738,112 @@ where
 		}
 
 		//check the keys if a key needs to be fetched
-		out.keys = if key_len >= 50 {
+		if key_len >= 50 && key_limit.is_none_or(|limit| key_len < limit) {
 			let last = out.keys.last().ok_or(SentcError::KeyNotFound)?;
 
 			let more_keys = fetch_keys(
-				base_url,
+				base_url.clone(),
 				&app_token,
 				jwt,
 				group_id,
 				out.access_by_group_as_member.as_deref(),
 				last.time,
 				last.group_key_id.clone(),
+				key_limit.map(|limit| limit - key_len),
 			)
 			.await?;
 
-			[out.keys, more_keys].into_iter().flatten().collect()
-		} else {
-			out.keys
-		};
+			out.keys.extend(more_keys);
+		}
+
+		if let Some(limit) = key_limit {
+			out.keys.truncate(limit.max(1));
+
+			//the hmac and sortable keys are encrypted by an older group key, this key is needed too
+			let required_keys = out
+				.hmac_keys
+				.iter()
+				.map(|k| &k.encrypted_hmac_encryption_key_id)
+				.chain(
+					out.sortable_keys
+						.iter()
+						.map(|k| &k.encrypted_sortable_encryption_key_id),
+				);
+
+			for key_id in required_keys {
+				if !out.keys.iter().any(|k| &k.group_key_id == key_id) {
+					let key = get_group_key(
+						base_url.clone(),
+						&app_token,
+						jwt,
+						group_id,
+						key_id,
+						out.access_by_group_as_member.as_deref(),
+					)
+					.await?;
+
+					out.keys.push(key);
+				}
+			}
+		}
 
-		let fetch_type = if parent || out.access_by_group_as_member.is_some() {
-			let mut missing_keys = Vec::new();
+		let fetch_type = Self::check_missing_private_keys(
+			&out.keys,
+			parent || out.access_by_group_as_member.is_some(),
+			user,
+			parent_group,
+		)?;
 
+		//No extra check for hmac or sortable because all keys are already fetched here. if the key does not exist then something was wrong.
+
+		Ok((out, fetch_type))
+	}
+
+	#[allow(clippy::type_complexity)]
+	fn check_missing_private_keys(
+		keys: &[GroupKeyServerOutput],
+		from_group: bool,
+		user: Option<&User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		parent_group: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+	) -> Result<GroupFetchResult, SentcError>
+	{
+		let mut missing_keys = Vec::new();
+
+		if from_group {
 			let pg = parent_group.ok_or(SentcError::GroupNotFound)?;
 
-			for k in out.keys.iter() {
+			for k in keys {
 				let key_id = &k.user_public_key_id;
 
 				//get group key
-				if pg.has_group_key(key_id).is_none() {
+				if pg.has_group_key(key_id).is_none() && !missing_keys.contains(key_id) {
 					missing_keys.push(key_id.to_string());
 				}
 			}
 
 			if !missing_keys.is_empty() {
-				GroupFetchResult::MissingGroupKeys(missing_keys)
-			} else {
-				GroupFetchResult::Ok
+				return Ok(GroupFetchResult::MissingGroupKeys(missing_keys));
 			}
 		} else {
-			let mut missing_keys = Vec::new();
-
 			let u = user.ok_or(SentcError::UserNotFound)?;
 
-			for k in out.keys.iter() {
+			for k in keys {
 				let key_id = &k.user_public_key_id;
 
-				if u.has_user_keys(key_id).is_none() {
+				if u.has_user_keys(key_id).is_none() && !missing_keys.contains(key_id) {
 					missing_keys.push(key_id.to_string());
 				}
 			}
 
 			if !missing_keys.is_empty() {
-				GroupFetchResult::MissingUserKeys(missing_keys)
-			} else {
-				GroupFetchResult::Ok
+				return Ok(GroupFetchResult::MissingUserKeys(missing_keys));
 			}
-		};
-
-		//No extra check for hmac or sortable because all keys are already fetched here. if the key does not exist then something was wrong.
+		}
 
-		Ok((out, fetch_type))
+		Ok(GroupFetchResult::Ok)
 	}
 
-	#[allow(clippy::type_complexity)]
+	#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 	pub(crate) fn done_fetch_group(
 		base_url: String,
 		app_token: String,
@@ -1211,7 +1852,123 @@ where
 		user: Option<&User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
 		parent_group: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
 		verify_keys: GroupKeyVerifyKeys,
+		key_limit: Option<usize>,
 	) -> Result<Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>, SentcError>
+	{
+		let (mut group, keys, hmac_keys, sortable_keys) = Self::new_fetched_group(base_url, app_token, parent, data, key_limit);
+
+		//in data.keys are all keys of the group not only the first page
+		if let Some(vk) = verify_keys {
+			for (i, key) in keys.into_iter().enumerate() {
+				let verify_key = vk.get(i).copied().flatten();
+
+				group.decrypt_group_keys(user, parent_group, key, verify_key)?;
+			}
+		} else {
+			for key in keys {
+				group.decrypt_group_keys(user, parent_group, key, None)?;
+			}
+		}
+
+		group.decrypt_fetched_keys(hmac_keys, sortable_keys)?;
+
+		Ok(group)
+	}
+
+	/// Like done_fetch_group but the verify keys are fetched for every signed group key.
+	#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+	pub(crate) async fn done_fetch_group_verified(
+		base_url: String,
+		app_token: String,
+		parent: bool,
+		data: GroupOutData,
+		user: Option<&User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		parent_group: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		key_limit: Option<usize>,
+		mode: GroupKeyVerifyMode,
+	) -> Result<
+		(
+			Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+			Vec<GroupKeyVerifyWarning>,
+		),
+		SentcError,
+	>
+	{
+		let (mut group, keys, hmac_keys, sortable_keys) = Self::new_fetched_group(base_url, app_token, parent, data, key_limit);
+
+		//the same user can sign many keys, None if the verify key can't be fetched
+		let mut verify_keys: HashMap<(UserId, SignKeyPairId), Option<UserVerifyKeyData>> = HashMap::new();
+		let mut warnings = Vec::new();
+
+		for key in keys {
+			let key_id = key.group_key_id.clone();
+
+			let (signer, sig) = match (
+				&key.signed_by_user_id,
+				&key.signed_by_user_sign_key_id,
+				&key.group_key_sig,
+			) {
+				(Some(user_id), Some(sign_key_id), Some(sig)) => ((user_id.clone(), sign_key_id.clone()), sig.clone()),
+				_ => {
+					if mode == GroupKeyVerifyMode::Fail {
+						return Err(SentcError::GroupKeyNotSigned(key_id));
+					}
+
+					warnings.push(GroupKeyVerifyWarning::NotSigned(key_id));
+					group.decrypt_group_keys(user, parent_group, key, None)?;
+
+					continue;
+				},
+			};
+
+			if !verify_keys.contains_key(&signer) {
+				let verify_key = match get_user_verify_key_data(group.base_url.clone(), &group.app_token, &signer.0, &signer.1).await {
+					Ok(verify_key) => Some(verify_key),
+					Err(e) if mode == GroupKeyVerifyMode::Fail => return Err(e),
+					Err(_) => None,
+				};
+
+				verify_keys.insert(signer.clone(), verify_key);
+			}
+
+			//verify the decrypted key afterward, so the key is not needed again to load it without verification
+			group.decrypt_group_keys(user, parent_group, key, None)?;
+
+			let verify_key = match verify_keys.get(&signer) {
+				Some(Some(verify_key)) => verify_key,
+				_ => {
+					warnings.push(GroupKeyVerifyWarning::VerifyKeyNotFound(key_id));
+					continue;
+				},
+			};
+
+			if !group.verify_group_key(&key_id, verify_key, &sig) {
+				if mode == GroupKeyVerifyMode::Fail {
+					return Err(SentcError::GroupKeyVerifyFailed(key_id));
+				}
+
+				warnings.push(GroupKeyVerifyWarning::InvalidSignature(key_id));
+			}
+		}
+
+		group.decrypt_fetched_keys(hmac_keys, sortable_keys)?;
+
+		Ok((group, warnings))
+	}
+
+	#[allow(clippy::type_complexity)]
+	fn new_fetched_group(
+		base_url: String,
+		app_token: String,
+		parent: bool,
+		data: GroupOutData,
+		key_limit: Option<usize>,
+	) -> (
+		Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+		Vec<GroupKeyServerOutput>,
+		Vec<GroupHmacData>,
+		Vec<GroupSortableData>,
+	)
 	{
 		let key_len = data.keys.len();
 
@@ -1235,28 +1992,24 @@ where
 
 		group.set_newest_key_id(data.keys[0].group_key_id.clone());
 
-		//in data.keys are all keys of the group not only the first page
-		if let Some(vk) = verify_keys {
-			for (i, key) in data.keys.into_iter().enumerate() {
-				let verify_key = vk.get(i).copied().flatten();
+		//with a limit, the keys can be the newest keys only
+		group.key_limit = key_limit;
+		group.older_keys_missing = key_limit.is_some_and(|limit| key_len >= limit);
 
-				group.decrypt_group_keys(user, parent_group, key, verify_key)?;
-			}
-		} else {
-			for key in data.keys {
-				group.decrypt_group_keys(user, parent_group, key, None)?;
-			}
-		}
+		(group, data.keys, data.hmac_keys, data.sortable_keys)
+	}
 
-		for search_key in data.hmac_keys {
-			group.decrypt_search_key(search_key)?;
+	fn decrypt_fetched_keys(&mut self, hmac_keys: Vec<GroupHmacData>, sortable_keys: Vec<GroupSortableData>) -> Result<(), SentcError>
+	{
+		for search_key in hmac_keys {
+			self.decrypt_search_key(search_key)?;
 		}
 
-		for sortable_key in data.sortable_keys {
-			group.decrypt_sort_key(sortable_key)?;
+		for sortable_key in sortable_keys {
+			self.decrypt_sort_key(sortable_key)?;
 		}
 
-		Ok(group)
+		Ok(())
 	}
 
 	#[allow(clippy::type_complexity)]
@@ -1292,6 +2045,28 @@ where
 		self.set_keys(private_key, fetched_keys, verify_key)
 	}
 
+	/// Check the signature of a decrypted group key. Returns false if the signature or the verify key is invalid.
+	fn verify_group_key(&self, key_id: &str, verify_key: &UserVerifyKeyData, sig: &str) -> bool
+	{
+		let key = match self.get_group_key(key_id) {
+			Some(key) => key,
+			None => return false,
+		};
+
+		let (vk, sig) = match (
+			SignC::vk_inner_from_pem(&verify_key.verify_key_pem, &verify_key.verify_key_alg),
+			SignC::sig_from_string(sig, &verify_key.verify_key_alg),
+		) {
+			(Ok(vk), Ok(sig)) => (vk, sig),
+			_ => return false,
+		};
+
+		key.group_key
+			.get_key()
+			.verify_key(&vk, &sig)
+			.unwrap_or(false)
+	}
+
 	fn decrypt_search_key(&mut self, hmac_key: GroupHmacData) -> Result<(), SentcError>
 	{
 		let key_id = &hmac_key.encrypted_hmac_encryption_key_id;
@@ -1331,6 +2106,7 @@ where
 	}
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn fetch_keys(
 	base_url: String,
 	app_token: &str,
@@ -1339,6 +2115,7 @@ async fn fetch_keys(
 	get_access_group_as_member: Option<&str>,
 	last_key_time: u128,
 	last_key_id: SymKeyId,
+	limit: Option<usize>,
 ) -> Result<Vec<GroupKeyServerOutput>, SentcError>
 {
 	//only fetch them, not decrypting them
@@ -1362,16 +2139,21 @@ async fn fetch_keys(
 
 		let key_len = fetched_key.len();
 
-		let last = fetched_key.last().ok_or(SentcError::KeyNotFound)?;
+		//the last page was full but there are no more keys
+		let last = match fetched_key.last() {
+			Some(k) => k,
+			None => break,
+		};
+
 		last_key_time = last.time;
 		last_key_id = last.group_key_id.clone();
 
-		fetched_keys.push(fetched_key);
+		fetched_keys.extend(fetched_key);
 
-		if key_len < 50 {
+		if key_len < 50 || limit.is_some_and(|limit| fetched_keys.len() >= limit) {
 			break;
 		}
 	}
 
-	Ok(fetched_keys.into_iter().flatten().collect())
+	Ok(fetched_keys)
 }
@@ -0,0 +1,154 @@
+use sentc_crypto::group::{
+	check_create_sub_group,
+	check_delete_user_rank,
+	check_get_join_reqs,
+	check_group_delete,
+	check_kick_user,
+	check_make_invite_req,
+	check_sent_join_req_list,
+};
+use sentc_crypto::SdkError;
+
+use crate::error::SentcError;
+
+/// The rank of a group member.
+///
+/// A lower rank number got more permissions. The order of the enum follows the rank number,
+/// so `GroupRank::Creator < GroupRank::Member`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GroupRank
+{
+	/// Rank 0, has full control of the group.
+	Creator,
+	/// Rank 1, has full control except removing the creator.
+	Admin,
+	/// Rank 2, can manage users: invites, join requests and kicking members with rank 2 or lower.
+	Manager,
+	/// Rank 3, a normal member rank which can be used for app specific actions, e.g. content management.
+	ContentManager,
+	/// Rank 4, the default rank of a new member.
+	Member,
+}
+
+/// The actions of a group which requires a rank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupAction
+{
+	Invite,
+	ManageJoinRequests,
+	Kick,
+	UpdateRank,
+	StopInvites,
+	RotateKeys,
+	CreateChildGroup,
+	GetSentJoinRequests,
+	DeleteGroup,
+	TransferOwnership,
+}
+
+impl GroupRank
+{
+	pub fn as_i32(&self) -> i32
+	{
+		match self {
+			Self::Creator => 0,
+			Self::Admin => 1,
+			Self::Manager => 2,
+			Self::ContentManager => 3,
+			Self::Member => 4,
+		}
+	}
+
+	/// Check if the rank is allowed to do this action.
+	///
+	/// Uses the rank checks of sentc-crypto, so the result is the same as the check of the request.
+	pub fn can(&self, action: GroupAction) -> bool
+	{
+		let rank = self.as_i32();
+
+		match action {
+			GroupAction::Invite => check_make_invite_req(rank).is_ok(),
+			GroupAction::ManageJoinRequests => check_get_join_reqs(rank).is_ok(),
+			GroupAction::Kick => check_delete_user_rank(rank).is_ok(),
+			GroupAction::StopInvites | GroupAction::CreateChildGroup => check_create_sub_group(rank).is_ok(),
+			GroupAction::GetSentJoinRequests => check_sent_join_req_list(rank).is_ok(),
+			GroupAction::DeleteGroup => check_group_delete(rank).is_ok(),
+			//sentc-crypto got no own check for these actions, both are only allowed for admins like in prepare_change_rank
+			GroupAction::UpdateRank | GroupAction::RotateKeys => *self <= Self::Admin,
+			//there is only one creator
+			GroupAction::TransferOwnership => *self == Self::Creator,
+		}
+	}
+
+	pub fn can_invite(&self) -> bool
+	{
+		self.can(GroupAction::Invite)
+	}
+
+	pub fn can_manage_join_requests(&self) -> bool
+	{
+		self.can(GroupAction::ManageJoinRequests)
+	}
+
+	/// Check if a member with the given rank can be kicked.
+	///
+	/// Only members with the same or a higher rank number can be kicked.
+	pub fn can_kick(&self, member: GroupRank) -> bool
+	{
+		check_kick_user(member.as_i32(), self.as_i32()).is_ok()
+	}
+
+	pub fn can_update_rank(&self) -> bool
+	{
+		self.can(GroupAction::UpdateRank)
+	}
+
+	pub fn can_rotate_keys(&self) -> bool
+	{
+		self.can(GroupAction::RotateKeys)
+	}
+
+	pub fn can_create_child_group(&self) -> bool
+	{
+		self.can(GroupAction::CreateChildGroup)
+	}
+
+	pub fn can_delete_group(&self) -> bool
+	{
+		self.can(GroupAction::DeleteGroup)
+	}
+
+	pub(crate) fn check(&self, action: GroupAction) -> Result<(), SentcError>
+	{
+		if !self.can(action) {
+			return Err(SentcError::GroupPermission(*self, action));
+		}
+
+		Ok(())
+	}
+}
+
+impl TryFrom<i32> for GroupRank
+{
+	type Error = SentcError;
+
+	fn try_from(value: i32) -> Result<Self, Self::Error>
+	{
+		match value {
+			0 => Ok(Self::Creator),
+			1 => Ok(Self::Admin),
+			2 => Ok(Self::Manager),
+			3 => Ok(Self::ContentManager),
+			4 => Ok(Self::Member),
+			_ => Err(SentcError::Sdk(SdkError::GroupRank)),
+		}
+	}
+}
+
+impl From<GroupRank> for i32
+{
+	fn from(value: GroupRank) -> Self
+	{
+		value.as_i32()
+	}
+}
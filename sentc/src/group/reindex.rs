@@ -0,0 +1,258 @@
+use std::str::FromStr;
+
+use sentc_crypto::sdk_common::user::UserVerifyKeyData;
+use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKeyGen};
+use sentc_crypto::sdk_utils::cryptomat::{
+	PkFromUserKeyWrapper,
+	SearchableKeyComposerWrapper,
+	SearchableKeyWrapper,
+	SignComposerWrapper,
+	SignKeyPairWrapper,
+	SortableKeyComposerWrapper,
+	SortableKeyWrapper,
+	StaticKeyComposerWrapper,
+	StaticKeyPairWrapper,
+	SymKeyComposerWrapper,
+	SymKeyGenWrapper,
+	VerifyKFromUserKeyWrapper,
+};
+
+use crate::error::SentcError;
+use crate::group::Group;
+
+/// The value of an item to re-index.
+pub enum ReindexData
+{
+	/// The plaintext value.
+	Plain(String),
+	/// A string encrypted with encrypt_string_sync.
+	EncryptedString(String),
+	/// Utf-8 bytes encrypted with encrypt_sync.
+	Encrypted(Vec<u8>),
+}
+
+pub struct ReindexItem
+{
+	pub id: String,
+	pub data: ReindexData,
+}
+
+/// Create the search hashes like create_search_raw.
+#[derive(Clone, Copy)]
+pub struct ReindexSearch
+{
+	pub full: bool,
+	pub limit: Option<usize>,
+}
+
+#[derive(Clone, Copy)]
+pub enum ReindexSortable
+{
+	/// The value is an u64 and is encrypted like encrypt_sortable_raw_number.
+	Number,
+	/// The value is encrypted like encrypt_sortable_raw_string with the max_len.
+	String(Option<usize>),
+	/// The value is an i64 and is encrypted like encrypt_sortable_raw_value.
+	Signed,
+	/// The value is a f64 and is encrypted like encrypt_sortable_raw_value.
+	Float,
+	/// Parse the value into a SortableValue encoding (e.g. a timestamp or a fixed-point number)
+	/// and encrypt it like encrypt_sortable_raw_value. None if the value is invalid.
+	Value(fn(&str) -> Option<u64>),
+}
+
+pub struct ReindexOptions
+{
+	pub search: Option<ReindexSearch>,
+	pub sortable: Option<ReindexSortable>,
+	/// How many items are in one batch. Default is 100.
+	pub batch_size: usize,
+}
+
+impl Default for ReindexOptions
+{
+	fn default() -> Self
+	{
+		Self {
+			search: None,
+			sortable: None,
+			batch_size: 100,
+		}
+	}
+}
+
+/// The new index of an item, created with the newest keys.
+pub struct ReindexOutput
+{
+	pub id: String,
+	/// Empty if no search options are set.
+	pub hashes: Vec<String>,
+	/// Empty if no sortable options are set.
+	///
+	/// One number for Number and String, the encrypted digits of the value for the other options.
+	/// Use sortable::to_sortable_string to store the digits as one string.
+	pub sortable: Vec<u64>,
+}
+
+/// A finished batch with the progress of the re-indexing.
+pub struct ReindexBatch<'a, T = ReindexOutput>
+{
+	pub items: &'a [T],
+	/// The count of items which are done, including this batch.
+	pub done: usize,
+	pub total: usize,
+	/// The id of the hmac key which was used for the hashes.
+	pub hmac_key_id: &'a str,
+	/// The id of the sortable key which was used for the sortable values.
+	pub sortable_key_id: &'a str,
+}
+
+impl<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+	Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+where
+	SGen: SymKeyGenWrapper,
+	StGen: StaticKeyPairWrapper,
+	SignGen: SignKeyPairWrapper,
+	SearchGen: SearchableKeyGen,
+	SortGen: SortableKeyGen,
+	SC: SymKeyComposerWrapper,
+	StC: StaticKeyComposerWrapper,
+	SignC: SignComposerWrapper,
+	SearchC: SearchableKeyComposerWrapper,
+	SortC: SortableKeyComposerWrapper,
+	PC: PkFromUserKeyWrapper,
+	VC: VerifyKFromUserKeyWrapper,
+	PwH: PwHash,
+{
+	/// Check if an index was created with older keys than the newest keys.
+	///
+	/// Pass the key ids which are stored with the hashes (from create_search) or the sortable values (from encrypt_sortable_number).
+	pub fn needs_reindex(&self, hmac_key_id: Option<&str>, sortable_key_id: Option<&str>) -> bool
+	{
+		let hmac_outdated = hmac_key_id.is_some_and(|id| id != self.get_newest_hmac_key().get_id());
+		let sortable_outdated = sortable_key_id.is_some_and(|id| id != self.get_newest_sortable_key().get_id());
+
+		hmac_outdated || sortable_outdated
+	}
+
+	/// Create the search hashes and sortable values of the items again with the newest keys.
+	///
+	/// Encrypted values are decrypted with the group keys first.
+	/// The items are processed in batches, after each batch on_batch is called to store the new index and to report the progress.
+	/// If on_batch returns an error the re-indexing stops.
+	///
+	/// Returns the count of re-indexed items.
+	pub fn reindex<F>(
+		&self,
+		items: &[ReindexItem],
+		options: &ReindexOptions,
+		verify_key: Option<&UserVerifyKeyData>,
+		on_batch: F,
+	) -> Result<usize, SentcError>
+	where
+		F: FnMut(ReindexBatch) -> Result<(), SentcError>,
+	{
+		let hmac_key = self.get_newest_hmac_key();
+		let sortable_key = self.get_newest_sortable_key();
+
+		self.reindex_with(
+			items,
+			options.batch_size,
+			verify_key,
+			|item, value| {
+				let hashes = match options.search {
+					Some(search) => hmac_key.create_searchable_raw(&value, search.full, search.limit)?,
+					None => Vec::new(),
+				};
+
+				let sortable = match options.sortable {
+					Some(ReindexSortable::Number) => vec![self.encrypt_sortable_raw_number(parse_value(item, &value)?)?],
+					Some(ReindexSortable::String(max_len)) => vec![sortable_key.encrypt_raw_string(&value, max_len)?],
+					Some(ReindexSortable::Signed) => self.encrypt_sortable_raw_value(&parse_value::<i64>(item, &value)?)?,
+					Some(ReindexSortable::Float) => self.encrypt_sortable_raw_value(&parse_value::<f64>(item, &value)?)?,
+					Some(ReindexSortable::Value(parse)) => {
+						let number = parse(&value).ok_or_else(|| SentcError::ReindexInvalidValue(item.id.clone()))?;
+
+						self.encrypt_sortable_raw_value(&number)?
+					},
+					None => Vec::new(),
+				};
+
+				Ok(ReindexOutput {
+					id: item.id.clone(),
+					hashes,
+					sortable,
+				})
+			},
+			on_batch,
+		)
+	}
+
+	/// Re-index the items with an own index function.
+	///
+	/// Use this for indexes which are not covered by the options,
+	/// like the multi-field index of the SearchIndexBuilder or a value with multiple sortable fields.
+	/// The index function gets the item with the decrypted value and must use the newest keys of the group.
+	///
+	/// Returns the count of re-indexed items.
+	pub fn reindex_with<T, I, F>(
+		&self,
+		items: &[ReindexItem],
+		batch_size: usize,
+		verify_key: Option<&UserVerifyKeyData>,
+		mut index: I,
+		mut on_batch: F,
+	) -> Result<usize, SentcError>
+	where
+		I: FnMut(&ReindexItem, String) -> Result<T, SentcError>,
+		F: FnMut(ReindexBatch<T>) -> Result<(), SentcError>,
+	{
+		let hmac_key_id = self.get_newest_hmac_key().get_id();
+		let sortable_key_id = self.get_newest_sortable_key().get_id();
+
+		let total = items.len();
+		let mut done = 0;
+
+		for chunk in items.chunks(batch_size.max(1)) {
+			let mut out = Vec::with_capacity(chunk.len());
+
+			for item in chunk {
+				let value = self.reindex_value(item, verify_key)?;
+
+				out.push(index(item, value)?);
+			}
+
+			done += out.len();
+
+			on_batch(ReindexBatch {
+				items: &out,
+				done,
+				total,
+				hmac_key_id,
+				sortable_key_id,
+			})?;
+		}
+
+		Ok(done)
+	}
+
+	fn reindex_value(&self, item: &ReindexItem, verify_key: Option<&UserVerifyKeyData>) -> Result<String, SentcError>
+	{
+		match &item.data {
+			ReindexData::Plain(value) => Ok(value.clone()),
+			ReindexData::EncryptedString(value) => self.decrypt_string_sync(value, verify_key),
+			ReindexData::Encrypted(value) => {
+				let decrypted = self.decrypt_sync(value, verify_key)?;
+
+				String::from_utf8(decrypted).map_err(|_| SentcError::ReindexInvalidValue(item.id.clone()))
+			},
+		}
+	}
+}
+
+fn parse_value<T: FromStr>(item: &ReindexItem, value: &str) -> Result<T, SentcError>
+{
+	value
+		.parse()
+		.map_err(|_| SentcError::ReindexInvalidValue(item.id.clone()))
+}
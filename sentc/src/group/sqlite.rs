@@ -0,0 +1,115 @@
+use sentc_crypto::sdk_common::user::UserVerifyKeyData;
+use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKeyGen};
+use sentc_crypto::sdk_utils::cryptomat::{
+	PkFromUserKeyWrapper,
+	SearchableKeyComposerWrapper,
+	SignComposerWrapper,
+	SignKeyPairWrapper,
+	SortableKeyComposerWrapper,
+	StaticKeyComposerWrapper,
+	StaticKeyPairWrapper,
+	SymKeyComposerWrapper,
+	SymKeyGenWrapper,
+	VerifyKFromUserKeyWrapper,
+};
+
+use crate::error::SentcError;
+use crate::group::Group;
+use crate::sortable::{to_sortable_string, SortableValue};
+use crate::sqlite::{EncryptedBlob, EncryptedText, SearchHash, SortableNumber, SortableText};
+
+impl<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+	Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+where
+	SGen: SymKeyGenWrapper,
+	StGen: StaticKeyPairWrapper,
+	SignGen: SignKeyPairWrapper,
+	SearchGen: SearchableKeyGen,
+	SortGen: SortableKeyGen,
+	SC: SymKeyComposerWrapper,
+	StC: StaticKeyComposerWrapper,
+	SignC: SignComposerWrapper,
+	SearchC: SearchableKeyComposerWrapper,
+	SortC: SortableKeyComposerWrapper,
+	PC: PkFromUserKeyWrapper,
+	VC: VerifyKFromUserKeyWrapper,
+	PwH: PwHash,
+{
+	//encrypted columns
+
+	pub fn sql_encrypt(&self, data: &[u8]) -> Result<EncryptedBlob, SentcError>
+	{
+		Ok(EncryptedBlob(self.encrypt_sync(data)?))
+	}
+
+	pub fn sql_decrypt(&self, value: &EncryptedBlob, verify_key: Option<&UserVerifyKeyData>) -> Result<Vec<u8>, SentcError>
+	{
+		self.decrypt_sync(&value.0, verify_key)
+	}
+
+	pub fn sql_encrypt_string(&self, data: &str) -> Result<EncryptedText, SentcError>
+	{
+		Ok(EncryptedText(self.encrypt_string_sync(data)?))
+	}
+
+	pub fn sql_decrypt_string(&self, value: &EncryptedText, verify_key: Option<&UserVerifyKeyData>) -> Result<String, SentcError>
+	{
+		self.decrypt_string_sync(&value.0, verify_key)
+	}
+
+	/// Read the encrypted blob column of the row and decrypt it.
+	pub fn sql_get_decrypted(&self, row: &rusqlite::Row, index: usize, verify_key: Option<&UserVerifyKeyData>) -> Result<Vec<u8>, SentcError>
+	{
+		let value: EncryptedBlob = row.get(index)?;
+
+		self.sql_decrypt(&value, verify_key)
+	}
+
+	/// Read the encrypted text column of the row and decrypt it.
+	pub fn sql_get_decrypted_string(&self, row: &rusqlite::Row, index: usize, verify_key: Option<&UserVerifyKeyData>) -> Result<String, SentcError>
+	{
+		let value: EncryptedText = row.get(index)?;
+
+		self.sql_decrypt_string(&value, verify_key)
+	}
+
+	//______________________________________________________________________________________________
+	//searchable columns
+
+	/// Create the hashes to store in the hash column, one row for each hash.
+	pub fn sql_create_search(&self, data: &str, full: bool, limit: Option<usize>) -> Result<Vec<SearchHash>, SentcError>
+	{
+		Ok(self
+			.create_search_raw(data, full, limit)?
+			.into_iter()
+			.map(SearchHash)
+			.collect())
+	}
+
+	/// Create the hash to query the hash column.
+	pub fn sql_search(&self, data: &str) -> Result<SearchHash, SentcError>
+	{
+		Ok(SearchHash(self.search(data)?))
+	}
+
+	//______________________________________________________________________________________________
+	//sortable columns
+
+	pub fn sql_sortable_number(&self, number: u64) -> Result<SortableNumber, SentcError>
+	{
+		Ok(SortableNumber(self.encrypt_sortable_raw_number(number)?))
+	}
+
+	pub fn sql_sortable_string(&self, data: &str, max_len: Option<usize>) -> Result<SortableNumber, SentcError>
+	{
+		Ok(SortableNumber(self.encrypt_sortable_raw_string(data, max_len)?))
+	}
+
+	/// Encrypt signed numbers, floats, timestamps or fixed-point numbers for a text column.
+	pub fn sql_sortable_value(&self, value: &impl SortableValue) -> Result<SortableText, SentcError>
+	{
+		Ok(SortableText(to_sortable_string(
+			&self.encrypt_sortable_raw_value(value)?,
+		)))
+	}
+}
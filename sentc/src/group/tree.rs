@@ -0,0 +1,494 @@
+use std::collections::HashMap;
+use std::iter;
+
+use futures_util::{stream, StreamExt, TryStreamExt};
+use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKeyGen};
+use sentc_crypto::sdk_utils::cryptomat::{
+	PkFromUserKeyWrapper,
+	SearchableKeyComposerWrapper,
+	SignComposerWrapper,
+	SignKeyPairWrapper,
+	SortableKeyComposerWrapper,
+	StaticKeyComposerWrapper,
+	StaticKeyPairWrapper,
+	SymKeyComposerWrapper,
+	SymKeyGenWrapper,
+	VerifyKFromUserKeyWrapper,
+};
+
+use crate::crypto_common::group::GroupChildrenList;
+use crate::crypto_common::GroupId;
+use crate::error::SentcError;
+use crate::group::Group;
+use crate::net_helper::check_jwt;
+
+#[derive(Clone)]
+pub struct GroupTreeOptions
+{
+	/// How many levels of children are loaded. 0 loads only the root group.
+	pub max_depth: usize,
+	/// Max. requests at the same time.
+	pub concurrency: usize,
+}
+
+impl Default for GroupTreeOptions
+{
+	fn default() -> Self
+	{
+		Self {
+			max_depth: 10,
+			concurrency: 5,
+		}
+	}
+}
+
+/// A failed request for a group of the tree.
+#[derive(Debug)]
+pub struct GroupTreeError
+{
+	pub group_id: GroupId,
+	pub error: SentcError,
+}
+
+#[allow(clippy::type_complexity)]
+pub struct GroupTreeNode<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+where
+	SGen: SymKeyGenWrapper,
+	StGen: StaticKeyPairWrapper,
+	SignGen: SignKeyPairWrapper,
+	SearchGen: SearchableKeyGen,
+	SortGen: SortableKeyGen,
+	SC: SymKeyComposerWrapper,
+	StC: StaticKeyComposerWrapper,
+	SignC: SignComposerWrapper,
+	SearchC: SearchableKeyComposerWrapper,
+	SortC: SortableKeyComposerWrapper,
+	PC: PkFromUserKeyWrapper,
+	VC: VerifyKFromUserKeyWrapper,
+	PwH: PwHash,
+{
+	group: Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+	parent_id: Option<GroupId>,
+	children: Vec<GroupId>,
+	depth: usize,
+}
+
+impl<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+	GroupTreeNode<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+where
+	SGen: SymKeyGenWrapper,
+	StGen: StaticKeyPairWrapper,
+	SignGen: SignKeyPairWrapper,
+	SearchGen: SearchableKeyGen,
+	SortGen: SortableKeyGen,
+	SC: SymKeyComposerWrapper,
+	StC: StaticKeyComposerWrapper,
+	SignC: SignComposerWrapper,
+	SearchC: SearchableKeyComposerWrapper,
+	SortC: SortableKeyComposerWrapper,
+	PC: PkFromUserKeyWrapper,
+	VC: VerifyKFromUserKeyWrapper,
+	PwH: PwHash,
+{
+	#[allow(clippy::type_complexity)]
+	pub fn get_group(&self) -> &Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+	{
+		&self.group
+	}
+
+	#[allow(clippy::type_complexity)]
+	pub fn get_group_mut(&mut self) -> &mut Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+	{
+		&mut self.group
+	}
+
+	/// The id of the parent group in the tree, None for the root group.
+	pub fn get_parent_id(&self) -> Option<&str>
+	{
+		self.parent_id.as_deref()
+	}
+
+	pub fn get_children(&self) -> &[GroupId]
+	{
+		&self.children
+	}
+
+	/// The level in the tree, 0 for the root group.
+	pub fn get_depth(&self) -> usize
+	{
+		self.depth
+	}
+}
+
+/// A group with all its descendants, keyed by the group id.
+///
+/// Each child group is fetched with its parent group, so the keys of the parent are always loaded in the tree.
+#[allow(clippy::type_complexity)]
+pub struct GroupTree<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+where
+	SGen: SymKeyGenWrapper,
+	StGen: StaticKeyPairWrapper,
+	SignGen: SignKeyPairWrapper,
+	SearchGen: SearchableKeyGen,
+	SortGen: SortableKeyGen,
+	SC: SymKeyComposerWrapper,
+	StC: StaticKeyComposerWrapper,
+	SignC: SignComposerWrapper,
+	SearchC: SearchableKeyComposerWrapper,
+	SortC: SortableKeyComposerWrapper,
+	PC: PkFromUserKeyWrapper,
+	VC: VerifyKFromUserKeyWrapper,
+	PwH: PwHash,
+{
+	root_id: GroupId,
+	nodes: HashMap<GroupId, GroupTreeNode<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+	options: GroupTreeOptions,
+}
+
+impl<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+	GroupTree<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+where
+	SGen: SymKeyGenWrapper,
+	StGen: StaticKeyPairWrapper,
+	SignGen: SignKeyPairWrapper,
+	SearchGen: SearchableKeyGen,
+	SortGen: SortableKeyGen,
+	SC: SymKeyComposerWrapper,
+	StC: StaticKeyComposerWrapper,
+	SignC: SignComposerWrapper,
+	SearchC: SearchableKeyComposerWrapper,
+	SortC: SortableKeyComposerWrapper,
+	PC: PkFromUserKeyWrapper,
+	VC: VerifyKFromUserKeyWrapper,
+	PwH: PwHash,
+{
+	/// Load all descendants of the root group until the max. depth.
+	///
+	/// The children of each level are fetched with max. concurrency requests at the same time.
+	#[allow(clippy::type_complexity)]
+	pub async fn load(
+		root: Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+		jwt: &str,
+		options: GroupTreeOptions,
+	) -> Result<Self, SentcError>
+	{
+		let root_id = root.group_id.clone();
+
+		let mut nodes = HashMap::new();
+		nodes.insert(
+			root_id.clone(),
+			GroupTreeNode {
+				group: root,
+				parent_id: None,
+				children: Vec::new(),
+				depth: 0,
+			},
+		);
+
+		let mut tree = Self {
+			root_id,
+			nodes,
+			options,
+		};
+
+		let root_id = tree.root_id.clone();
+
+		tree.load_children(jwt, root_id).await?;
+
+		Ok(tree)
+	}
+
+	/// Check the loaded groups for updates and finish the key rotations of the child groups.
+	///
+	/// Key rotations of the child groups are finished with the parent group.
+	/// A key rotation of the root group must be finished by the user or the parent of the root.
+	///
+	/// A failed group doesn't stop the refresh of the other groups, the errors are returned for each group.
+	/// The children lists are not fetched again, use reload_children after children were created or deleted.
+	pub async fn refresh(&mut self, jwt: &str) -> Result<Vec<GroupTreeError>, SentcError>
+	{
+		check_jwt(jwt)?;
+
+		let mut errors: Vec<GroupTreeError> = stream::iter(self.nodes.iter_mut().map(|(id, node)| {
+			async move {
+				node.group.group_update_check(jwt).await.err().map(|error| {
+					GroupTreeError {
+						group_id: id.clone(),
+						error,
+					}
+				})
+			}
+		}))
+		.buffer_unordered(self.options.concurrency.max(1))
+		.collect::<Vec<_>>()
+		.await
+		.into_iter()
+		.flatten()
+		.collect();
+
+		//the parent must finish the rotation before the children
+		let mut rotations: Vec<(usize, GroupId)> = self
+			.nodes
+			.iter()
+			.filter(|(id, node)| node.parent_id.is_some() && node.group.key_update && !errors.iter().any(|e| &e.group_id == *id))
+			.map(|(id, node)| (node.depth, id.clone()))
+			.collect();
+
+		rotations.sort();
+
+		for (_, id) in rotations {
+			let mut node = match self.nodes.remove(&id) {
+				Some(node) => node,
+				None => continue,
+			};
+
+			let parent = node
+				.parent_id
+				.as_ref()
+				.and_then(|parent_id| self.nodes.get(parent_id))
+				.map(|parent| &parent.group);
+
+			let res = node.group.finish_key_rotation(jwt, None, parent).await;
+
+			self.nodes.insert(id.clone(), node);
+
+			if let Err(error) = res {
+				errors.push(GroupTreeError {
+					group_id: id,
+					error,
+				});
+			}
+		}
+
+		Ok(errors)
+	}
+
+	/// Load the changed children below the group until the max. depth of the tree.
+	///
+	/// New children are loaded and deleted children are removed with their descendants.
+	pub async fn reload_children(&mut self, jwt: &str, group_id: &str) -> Result<(), SentcError>
+	{
+		check_jwt(jwt)?;
+
+		if !self.nodes.contains_key(group_id) {
+			return Err(SentcError::GroupNotFound);
+		}
+
+		self.load_children(jwt, group_id.to_string()).await
+	}
+
+	#[allow(clippy::type_complexity)]
+	pub fn get_root(&self) -> &Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+	{
+		//the root is never removed
+		&self.nodes[&self.root_id].group
+	}
+
+	#[allow(clippy::type_complexity)]
+	pub fn get(&self, group_id: &str) -> Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>
+	{
+		self.nodes.get(group_id).map(|node| &node.group)
+	}
+
+	#[allow(clippy::type_complexity)]
+	pub fn get_mut(
+		&mut self,
+		group_id: &str,
+	) -> Option<&mut Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>
+	{
+		self.nodes.get_mut(group_id).map(|node| &mut node.group)
+	}
+
+	#[allow(clippy::type_complexity)]
+	pub fn get_node(
+		&self,
+		group_id: &str,
+	) -> Option<&GroupTreeNode<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>
+	{
+		self.nodes.get(group_id)
+	}
+
+	/// The group and its parent group in the tree. The parent is None for the root group.
+	#[allow(clippy::type_complexity)]
+	pub fn get_with_parent(
+		&self,
+		group_id: &str,
+	) -> Option<(
+		&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+		Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+	)>
+	{
+		let node = self.nodes.get(group_id)?;
+
+		let parent = node
+			.parent_id
+			.as_ref()
+			.and_then(|parent_id| self.get(parent_id));
+
+		Some((&node.group, parent))
+	}
+
+	pub fn len(&self) -> usize
+	{
+		self.nodes.len()
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		self.nodes.is_empty()
+	}
+
+	/// Walk the tree depth first, starting with the root group.
+	#[allow(clippy::type_complexity)]
+	pub fn walk(&self)
+		-> impl Iterator<Item = &GroupTreeNode<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>
+	{
+		let mut stack = vec![&self.root_id];
+
+		iter::from_fn(move || {
+			let node = loop {
+				let id = stack.pop()?;
+
+				if let Some(node) = self.nodes.get(id) {
+					break node;
+				}
+			};
+
+			//reverse to walk the children in the fetched order
+			stack.extend(node.children.iter().rev());
+
+			Some(node)
+		})
+	}
+
+	/// All nodes which matches the predicate, in the order of walk.
+	#[allow(clippy::type_complexity)]
+	pub fn search<F>(
+		&self,
+		mut predicate: F,
+	) -> Vec<&GroupTreeNode<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>
+	where
+		F: FnMut(&GroupTreeNode<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>) -> bool,
+	{
+		self.walk().filter(|node| predicate(node)).collect()
+	}
+
+	/// The ids from the root group to the group.
+	pub fn get_path(&self, group_id: &str) -> Option<Vec<&str>>
+	{
+		let mut path = Vec::new();
+		let mut node = self.nodes.get(group_id)?;
+
+		loop {
+			path.push(node.group.group_id.as_str());
+
+			match &node.parent_id {
+				Some(parent_id) => node = self.nodes.get(parent_id)?,
+				None => break,
+			}
+		}
+
+		path.reverse();
+
+		Some(path)
+	}
+
+	//______________________________________________________________________________________________
+
+	async fn load_children(&mut self, jwt: &str, group_id: GroupId) -> Result<(), SentcError>
+	{
+		let limit = self.options.concurrency.max(1);
+
+		let mut depth = self.nodes.get(&group_id).map_or(0, |node| node.depth);
+		let mut level = vec![group_id];
+
+		while !level.is_empty() && depth < self.options.max_depth {
+			let nodes = &self.nodes;
+
+			let children: Vec<(GroupId, Vec<GroupChildrenList>)> = stream::iter(level)
+				.map(|id| {
+					async move {
+						let node = nodes.get(&id).ok_or(SentcError::GroupNotFound)?;
+
+						let children = node.group.get_children_stream(jwt).try_collect().await?;
+
+						Ok::<_, SentcError>((id, children))
+					}
+				})
+				.buffer_unordered(limit)
+				.try_collect()
+				.await?;
+
+			let new_children: Vec<(GroupId, GroupId)> = children
+				.iter()
+				.flat_map(|(parent_id, list)| {
+					list.iter()
+						.filter(|child| !nodes.contains_key(&child.group_id))
+						.map(move |child| (parent_id.clone(), child.group_id.clone()))
+				})
+				.collect();
+
+			let loaded: Vec<_> = stream::iter(new_children)
+				.map(|(parent_id, id)| {
+					async move {
+						let parent = &nodes
+							.get(&parent_id)
+							.ok_or(SentcError::GroupNotFound)?
+							.group;
+
+						let group = parent.get_child_group(&id, jwt).await?;
+
+						Ok::<_, SentcError>((parent_id, id, group))
+					}
+				})
+				.buffer_unordered(limit)
+				.try_collect()
+				.await?;
+
+			//only change the tree after all requests of the level are done
+			for (parent_id, id, group) in loaded {
+				self.nodes.insert(
+					id,
+					GroupTreeNode {
+						group,
+						parent_id: Some(parent_id),
+						children: Vec::new(),
+						depth: depth + 1,
+					},
+				);
+			}
+
+			level = Vec::new();
+
+			for (parent_id, list) in children {
+				let ids: Vec<GroupId> = list.into_iter().map(|child| child.group_id).collect();
+
+				let old_ids = match self.nodes.get_mut(&parent_id) {
+					Some(node) => std::mem::replace(&mut node.children, ids.clone()),
+					None => continue,
+				};
+
+				for id in old_ids {
+					if !ids.contains(&id) {
+						self.remove_subtree(&id);
+					}
+				}
+
+				level.extend(ids);
+			}
+
+			depth += 1;
+		}
+
+		Ok(())
+	}
+
+	fn remove_subtree(&mut self, group_id: &str)
+	{
+		if let Some(node) = self.nodes.remove(group_id) {
+			for child in node.children {
+				self.remove_subtree(&child);
+			}
+		}
+	}
+}
@@ -0,0 +1,483 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::TryStreamExt;
+use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKeyGen};
+use sentc_crypto::sdk_utils::cryptomat::{
+	PkFromUserKeyWrapper,
+	SearchableKeyComposerWrapper,
+	SignComposerWrapper,
+	SignKeyPairWrapper,
+	SortableKeyComposerWrapper,
+	StaticKeyComposerWrapper,
+	StaticKeyPairWrapper,
+	SymKeyComposerWrapper,
+	SymKeyGenWrapper,
+	VerifyKFromUserKeyWrapper,
+};
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::sleep;
+
+use crate::crypto_common::group::{GroupInviteReqList, GroupJoinReqList};
+use crate::crypto_common::{GroupId, UserId};
+use crate::error::SentcError;
+use crate::group::rank::{GroupAction, GroupRank};
+use crate::group::Group;
+use crate::user::User;
+
+pub enum GroupEvent
+{
+	/// The rank of the user in the group was changed.
+	RankChanged
+	{
+		group_id: GroupId,
+		old_rank: GroupRank,
+		new_rank: GroupRank,
+	},
+	/// A key rotation was started, the group needs to finish it.
+	KeyRotation
+	{
+		group_id: GroupId
+	},
+	/// The watcher finished the key rotation of the group and loaded the new keys.
+	KeyRotationFinished
+	{
+		group_id: GroupId
+	},
+	/// A new invite for the user.
+	Invite(GroupInviteReqList),
+	/// A new join request for a group where the user can accept join requests.
+	JoinRequest
+	{
+		group_id: GroupId, request: GroupJoinReqList
+	},
+	/// The check of a group failed, the other groups are still checked.
+	GroupError
+	{
+		group_id: GroupId, error: SentcError
+	},
+	/// A check failed, the next check is delayed.
+	Error(SentcError),
+}
+
+#[derive(Clone)]
+pub struct GroupWatcherOptions
+{
+	/// The time between two checks.
+	pub interval: Duration,
+	/// After a failed check the interval is doubled until this max. interval.
+	pub max_interval: Duration,
+	/// Finish the key rotations of groups which are fetched directly by the user.
+	///
+	/// Child and connected groups need the parent group to finish the rotation,
+	/// for these groups only a KeyRotation event is sent.
+	pub finish_key_rotation: bool,
+}
+
+impl Default for GroupWatcherOptions
+{
+	fn default() -> Self
+	{
+		Self {
+			interval: Duration::from_secs(30),
+			max_interval: Duration::from_secs(600),
+			finish_key_rotation: false,
+		}
+	}
+}
+
+//The changes of the reported invites, join requests and key rotations of one check.
+//These are only applied after the check is done, so no event is lost when the check failed.
+#[derive(Default)]
+struct SeenChanges
+{
+	invites: HashSet<GroupId>,
+	join_requests: Vec<(GroupId, HashSet<UserId>)>,
+	key_rotations: Vec<(GroupId, bool)>,
+}
+
+/// Checks the loaded groups of a user for changes.
+///
+/// Invites and join requests are only reported once, key rotations are reported until the rotation is finished.
+pub struct GroupWatcher
+{
+	options: GroupWatcherOptions,
+	invites: HashSet<GroupId>,
+	join_requests: HashSet<(GroupId, UserId)>,
+	key_rotations: HashSet<GroupId>,
+}
+
+impl GroupWatcher
+{
+	pub fn new(options: GroupWatcherOptions) -> Self
+	{
+		Self {
+			options,
+			invites: HashSet::new(),
+			join_requests: HashSet::new(),
+			key_rotations: HashSet::new(),
+		}
+	}
+
+	/// Check the user and the groups once and return the new events.
+	///
+	/// An error of a single group is returned as GroupError event and the other groups are still checked.
+	/// Only an error of the user (jwt or invites) is returned as error.
+	#[allow(clippy::type_complexity)]
+	pub async fn check<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>(
+		&mut self,
+		user: &User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+		groups: &mut [Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>],
+	) -> Result<Vec<GroupEvent>, SentcError>
+	where
+		SGen: SymKeyGenWrapper,
+		StGen: StaticKeyPairWrapper,
+		SignGen: SignKeyPairWrapper,
+		SearchGen: SearchableKeyGen,
+		SortGen: SortableKeyGen,
+		SC: SymKeyComposerWrapper,
+		StC: StaticKeyComposerWrapper,
+		SignC: SignComposerWrapper,
+		SearchC: SearchableKeyComposerWrapper,
+		SortC: SortableKeyComposerWrapper,
+		PC: PkFromUserKeyWrapper,
+		VC: VerifyKFromUserKeyWrapper,
+		PwH: PwHash,
+	{
+		let jwt = user.get_jwt()?;
+
+		let mut events = Vec::new();
+		let mut seen = SeenChanges::default();
+
+		self.check_invites(user, &mut events, &mut seen).await?;
+
+		for group in groups.iter_mut() {
+			self.check_group(user, group, jwt, &mut events, &mut seen)
+				.await;
+		}
+
+		self.apply(seen);
+
+		Ok(events)
+	}
+
+	#[allow(clippy::type_complexity)]
+	async fn check_invites<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>(
+		&self,
+		user: &User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+		events: &mut Vec<GroupEvent>,
+		seen: &mut SeenChanges,
+	) -> Result<(), SentcError>
+	where
+		SGen: SymKeyGenWrapper,
+		StGen: StaticKeyPairWrapper,
+		SignGen: SignKeyPairWrapper,
+		SearchGen: SearchableKeyGen,
+		SortGen: SortableKeyGen,
+		SC: SymKeyComposerWrapper,
+		StC: StaticKeyComposerWrapper,
+		SignC: SignComposerWrapper,
+		SearchC: SearchableKeyComposerWrapper,
+		SortC: SortableKeyComposerWrapper,
+		PC: PkFromUserKeyWrapper,
+		VC: VerifyKFromUserKeyWrapper,
+		PwH: PwHash,
+	{
+		let invites: Vec<GroupInviteReqList> = user.get_group_invites_stream().try_collect().await?;
+
+		//keep only the current invites, so an invite after a reject is reported again
+		for invite in invites {
+			seen.invites.insert(invite.group_id.clone());
+
+			if !self.invites.contains(&invite.group_id) {
+				events.push(GroupEvent::Invite(invite));
+			}
+		}
+
+		Ok(())
+	}
+
+	#[allow(clippy::type_complexity)]
+	async fn check_group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>(
+		&self,
+		user: &User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+		group: &mut Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+		jwt: &str,
+		events: &mut Vec<GroupEvent>,
+		seen: &mut SeenChanges,
+	) where
+		SGen: SymKeyGenWrapper,
+		StGen: StaticKeyPairWrapper,
+		SignGen: SignKeyPairWrapper,
+		SearchGen: SearchableKeyGen,
+		SortGen: SortableKeyGen,
+		SC: SymKeyComposerWrapper,
+		StC: StaticKeyComposerWrapper,
+		SignC: SignComposerWrapper,
+		SearchC: SearchableKeyComposerWrapper,
+		SortC: SortableKeyComposerWrapper,
+		PC: PkFromUserKeyWrapper,
+		VC: VerifyKFromUserKeyWrapper,
+		PwH: PwHash,
+	{
+		//the events before the error are kept, e.g. a rank change is only detected once
+		if let Err(error) = self
+			.check_group_changes(user, group, jwt, events, seen)
+			.await
+		{
+			events.push(GroupEvent::GroupError {
+				group_id: group.group_id.clone(),
+				error,
+			});
+		}
+	}
+
+	#[allow(clippy::type_complexity)]
+	async fn check_group_changes<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>(
+		&self,
+		user: &User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+		group: &mut Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+		jwt: &str,
+		events: &mut Vec<GroupEvent>,
+		seen: &mut SeenChanges,
+	) -> Result<(), SentcError>
+	where
+		SGen: SymKeyGenWrapper,
+		StGen: StaticKeyPairWrapper,
+		SignGen: SignKeyPairWrapper,
+		SearchGen: SearchableKeyGen,
+		SortGen: SortableKeyGen,
+		SC: SymKeyComposerWrapper,
+		StC: StaticKeyComposerWrapper,
+		SignC: SignComposerWrapper,
+		SearchC: SearchableKeyComposerWrapper,
+		SortC: SortableKeyComposerWrapper,
+		PC: PkFromUserKeyWrapper,
+		VC: VerifyKFromUserKeyWrapper,
+		PwH: PwHash,
+	{
+		let old_rank = group.get_group_rank()?;
+
+		group.group_update_check(jwt).await?;
+
+		let new_rank = group.get_group_rank()?;
+
+		if old_rank != new_rank {
+			events.push(GroupEvent::RankChanged {
+				group_id: group.group_id.clone(),
+				old_rank,
+				new_rank,
+			});
+		}
+
+		if group.key_update {
+			self.handle_key_rotation(user, group, jwt, events, seen)
+				.await?;
+		} else {
+			seen.key_rotations.push((group.group_id.clone(), false));
+		}
+
+		if new_rank.can(GroupAction::ManageJoinRequests) {
+			let requests: Vec<GroupJoinReqList> = group.get_join_requests_stream(jwt).try_collect().await?;
+
+			let mut current_requests = HashSet::with_capacity(requests.len());
+
+			for request in requests {
+				current_requests.insert(request.user_id.clone());
+
+				if !self
+					.join_requests
+					.contains(&(group.group_id.clone(), request.user_id.clone()))
+				{
+					events.push(GroupEvent::JoinRequest {
+						group_id: group.group_id.clone(),
+						request,
+					});
+				}
+			}
+
+			seen.join_requests
+				.push((group.group_id.clone(), current_requests));
+		}
+
+		Ok(())
+	}
+
+	#[allow(clippy::type_complexity)]
+	async fn handle_key_rotation<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>(
+		&self,
+		user: &User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+		group: &mut Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+		jwt: &str,
+		events: &mut Vec<GroupEvent>,
+		seen: &mut SeenChanges,
+	) -> Result<(), SentcError>
+	where
+		SGen: SymKeyGenWrapper,
+		StGen: StaticKeyPairWrapper,
+		SignGen: SignKeyPairWrapper,
+		SearchGen: SearchableKeyGen,
+		SortGen: SortableKeyGen,
+		SC: SymKeyComposerWrapper,
+		StC: StaticKeyComposerWrapper,
+		SignC: SignComposerWrapper,
+		SearchC: SearchableKeyComposerWrapper,
+		SortC: SortableKeyComposerWrapper,
+		PC: PkFromUserKeyWrapper,
+		VC: VerifyKFromUserKeyWrapper,
+		PwH: PwHash,
+	{
+		let group_id = group.group_id.clone();
+
+		//only groups of the user can be finished without the parent group
+		if self.options.finish_key_rotation && !group.from_parent && group.access_by_group_as_member.is_none() {
+			group.finish_key_rotation(jwt, Some(user), None).await?;
+
+			seen.key_rotations.push((group_id.clone(), false));
+			events.push(GroupEvent::KeyRotationFinished {
+				group_id,
+			});
+		} else {
+			if !self.key_rotations.contains(&group_id) {
+				events.push(GroupEvent::KeyRotation {
+					group_id: group_id.clone(),
+				});
+			}
+
+			seen.key_rotations.push((group_id, true));
+		}
+
+		Ok(())
+	}
+
+	fn apply(&mut self, seen: SeenChanges)
+	{
+		self.invites = seen.invites;
+
+		for (group_id, user_ids) in seen.join_requests {
+			self.join_requests.retain(|(id, _)| id != &group_id);
+			self.join_requests.extend(
+				user_ids
+					.into_iter()
+					.map(|user_id| (group_id.clone(), user_id)),
+			);
+		}
+
+		for (group_id, in_progress) in seen.key_rotations {
+			if in_progress {
+				self.key_rotations.insert(group_id);
+			} else {
+				self.key_rotations.remove(&group_id);
+			}
+		}
+	}
+
+	//like check but each group is only locked while it is checked
+	#[allow(clippy::type_complexity)]
+	async fn check_locked<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>(
+		&mut self,
+		user: &RwLock<User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		groups: &RwLock<Vec<Arc<RwLock<Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>>>>,
+	) -> Result<Vec<GroupEvent>, SentcError>
+	where
+		SGen: SymKeyGenWrapper,
+		StGen: StaticKeyPairWrapper,
+		SignGen: SignKeyPairWrapper,
+		SearchGen: SearchableKeyGen,
+		SortGen: SortableKeyGen,
+		SC: SymKeyComposerWrapper,
+		StC: StaticKeyComposerWrapper,
+		SignC: SignComposerWrapper,
+		SearchC: SearchableKeyComposerWrapper,
+		SortC: SortableKeyComposerWrapper,
+		PC: PkFromUserKeyWrapper,
+		VC: VerifyKFromUserKeyWrapper,
+		PwH: PwHash,
+	{
+		let user = user.read().await;
+		let jwt = user.get_jwt()?;
+
+		let mut events = Vec::new();
+		let mut seen = SeenChanges::default();
+
+		self.check_invites(&user, &mut events, &mut seen).await?;
+
+		//a snapshot of the list, so groups can be added or removed while the check is running
+		let list = groups.read().await.clone();
+
+		for group in list {
+			let mut group = group.write().await;
+
+			self.check_group(&user, &mut group, jwt, &mut events, &mut seen)
+				.await;
+		}
+
+		self.apply(seen);
+
+		Ok(events)
+	}
+
+	/// Check the groups in the interval until the receiver is dropped.
+	///
+	/// The user and each group are only locked while they are checked.
+	/// The jwt of the user is refreshed when it is expired.
+	/// After a failed check an Error event is sent and the interval is doubled until the max. interval.
+	#[allow(clippy::type_complexity)]
+	pub async fn run<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>(
+		mut self,
+		user: &RwLock<User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		groups: &RwLock<Vec<Arc<RwLock<Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>>>>,
+		sender: mpsc::Sender<GroupEvent>,
+	) where
+		SGen: SymKeyGenWrapper,
+		StGen: StaticKeyPairWrapper,
+		SignGen: SignKeyPairWrapper,
+		SearchGen: SearchableKeyGen,
+		SortGen: SortableKeyGen,
+		SC: SymKeyComposerWrapper,
+		StC: StaticKeyComposerWrapper,
+		SignC: SignComposerWrapper,
+		SearchC: SearchableKeyComposerWrapper,
+		SortC: SortableKeyComposerWrapper,
+		PC: PkFromUserKeyWrapper,
+		VC: VerifyKFromUserKeyWrapper,
+		PwH: PwHash,
+	{
+		let mut interval = self.options.interval;
+
+		while !sender.is_closed() {
+			let res = match self.check_locked(user, groups).await {
+				Err(SentcError::JwtExpired) => {
+					//check again with the new jwt, but only once per interval
+					match user.write().await.refresh_jwt().await {
+						Ok(_) => self.check_locked(user, groups).await,
+						Err(e) => Err(e),
+					}
+				},
+				res => res,
+			};
+
+			match res {
+				Ok(events) => {
+					interval = self.options.interval;
+
+					for event in events {
+						if sender.send(event).await.is_err() {
+							return;
+						}
+					}
+				},
+				Err(e) => {
+					interval = (interval * 2).min(self.options.max_interval);
+
+					if sender.send(GroupEvent::Error(e)).await.is_err() {
+						return;
+					}
+				},
+			}
+
+			sleep(interval).await;
+		}
+	}
+}
@@ -3,10 +3,13 @@ use sentc_crypto::fips_keys::core::sortable::NonSortableKeys;
 use sentc_crypto::fips_keys::util::{HmacKey, PublicKey, SecretKey, SignKey, SortableKey, SymmetricKey, VerifyKey};
 pub use sentc_crypto::keys::fips::{FipsGroupKeyData, FipsUserDataInt, FipsUserKeyDataInt};
 
+use crate::crypto_common::crypto::EncryptedHead;
+use crate::crypto_common::user::UserPublicKeyData;
+use crate::error::SentcError;
 use crate::group::Group;
 #[cfg(feature = "network")]
 use crate::user::net::UserLoginReturn;
-use crate::user::User;
+use crate::user::{crypto_sync, User};
 
 pub type FipsGroup = Group<
 	SymmetricKey,
@@ -59,3 +62,23 @@ pub type FipsUserLoginReturn = UserLoginReturn<
 
 #[cfg(feature = "network")]
 pub type RecPrepareLoginOtpOutput = sentc_crypto::sdk_utils::full::user::PrepareLoginOtpOutput<sentc_crypto::fips_keys::core::hmac::HmacKey>;
+
+pub fn encrypt_raw_for_user(data: &[u8], reply_key: &UserPublicKeyData) -> Result<(EncryptedHead, Vec<u8>), SentcError>
+{
+	crypto_sync::encrypt_raw_for_user::<PublicKey>(data, reply_key)
+}
+
+pub fn encrypt_for_user(data: &[u8], reply_key: &UserPublicKeyData) -> Result<Vec<u8>, SentcError>
+{
+	crypto_sync::encrypt_for_user::<PublicKey>(data, reply_key)
+}
+
+pub fn encrypt_string_for_user(data: &str, reply_key: &UserPublicKeyData) -> Result<String, SentcError>
+{
+	crypto_sync::encrypt_string_for_user::<PublicKey>(data, reply_key)
+}
+
+pub fn encrypt_for_users(data: &[u8], reply_keys: &[UserPublicKeyData]) -> Result<Vec<u8>, SentcError>
+{
+	crypto_sync::encrypt_for_users::<SymmetricKey, PublicKey>(data, reply_keys)
+}
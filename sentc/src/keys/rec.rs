@@ -2,10 +2,13 @@ pub use sentc_crypto::keys::rec::{RecGroupKeyData, RecUserDataInt, RecUserKeyDat
 use sentc_crypto::rec_keys::core::pw_hash::PwHasher;
 use sentc_crypto::rec_keys::util::{HmacKey, PublicKey, SecretKey, SignKey, SortableKey, SymmetricKey, VerifyKey};
 
+use crate::crypto_common::crypto::EncryptedHead;
+use crate::crypto_common::user::UserPublicKeyData;
+use crate::error::SentcError;
 use crate::group::Group;
 #[cfg(feature = "network")]
 use crate::user::net::UserLoginReturn;
-use crate::user::User;
+use crate::user::{crypto_sync, User};
 
 pub type RecGroup = Group<
 	SymmetricKey,
@@ -58,3 +61,23 @@ pub type RecUserLoginReturn = UserLoginReturn<
 
 #[cfg(feature = "network")]
 pub type RecPrepareLoginOtpOutput = sentc_crypto::sdk_utils::full::user::PrepareLoginOtpOutput<sentc_crypto::rec_keys::core::hmac::HmacKey>;
+
+pub fn encrypt_raw_for_user(data: &[u8], reply_key: &UserPublicKeyData) -> Result<(EncryptedHead, Vec<u8>), SentcError>
+{
+	crypto_sync::encrypt_raw_for_user::<PublicKey>(data, reply_key)
+}
+
+pub fn encrypt_for_user(data: &[u8], reply_key: &UserPublicKeyData) -> Result<Vec<u8>, SentcError>
+{
+	crypto_sync::encrypt_for_user::<PublicKey>(data, reply_key)
+}
+
+pub fn encrypt_string_for_user(data: &str, reply_key: &UserPublicKeyData) -> Result<String, SentcError>
+{
+	crypto_sync::encrypt_string_for_user::<PublicKey>(data, reply_key)
+}
+
+pub fn encrypt_for_users(data: &[u8], reply_keys: &[UserPublicKeyData]) -> Result<Vec<u8>, SentcError>
+{
+	crypto_sync::encrypt_for_users::<SymmetricKey, PublicKey>(data, reply_keys)
+}
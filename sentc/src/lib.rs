@@ -10,10 +10,12 @@
 //! # Advanced
 #![doc=include_str!("../doc/protocol.md")]
 #![doc=include_str!("../doc/end-to-end-encrypted-database.md")]
+#![doc=include_str!("../doc/store.md")]
 #![doc=include_str!("../doc/backend-only.md")]
 #![doc=include_str!("../doc/self-hosted.md")]
 #![allow(clippy::tabs_in_doc_comments, rustdoc::bare_urls)]
 
+pub mod encrypt_options;
 pub mod error;
 #[cfg(feature = "file")]
 pub mod file;
@@ -21,6 +23,12 @@ pub mod group;
 pub mod keys;
 #[cfg(feature = "network")]
 pub mod net_helper;
+pub mod searchable;
+pub mod sortable;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(feature = "store")]
+pub mod store;
 pub mod user;
 
 use std::collections::HashMap;
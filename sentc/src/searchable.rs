@@ -0,0 +1,251 @@
+use sentc_crypto::sdk_common::SymKeyId;
+use sentc_crypto::sdk_core::cryptomat::CryptoAlg;
+use sentc_crypto::sdk_utils::cryptomat::SearchableKeyWrapper;
+use sentc_crypto::sdk_utils::error::SdkUtilError;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SentcError;
+
+/// How the value of a field is hashed.
+#[derive(Clone, Copy)]
+pub enum SearchMode
+{
+	/// Only one hash of the full value for exact matches.
+	Full,
+	/// A hash for each prefix of the value, with an optional limit of bytes.
+	Prefix(Option<usize>),
+}
+
+/// The hashes of one field.
+///
+/// For the index: all hashes of the field. For a query: every hash must match.
+#[derive(Serialize, Deserialize)]
+pub struct SearchField
+{
+	pub name: String,
+	pub hashes: Vec<String>,
+}
+
+/// The output of the index and the query builder.
+#[derive(Serialize, Deserialize)]
+pub struct SearchIndex
+{
+	pub key_id: SymKeyId,
+	pub alg: String,
+	pub fields: Vec<SearchField>,
+}
+
+struct IndexField
+{
+	name: String,
+	value: String,
+	mode: SearchMode,
+	tokenize: bool,
+}
+
+/// Create the searchable hashes for multiple fields of an item.
+///
+/// Every hash contains the name of the field, so the same value in different fields got different hashes.
+pub struct SearchIndexBuilder<'a, K: SearchableKeyWrapper>
+{
+	key: &'a K,
+	fields: Vec<IndexField>,
+}
+
+impl<'a, K: SearchableKeyWrapper> SearchIndexBuilder<'a, K>
+{
+	pub fn new(key: &'a K) -> Self
+	{
+		Self {
+			key,
+			fields: Vec::new(),
+		}
+	}
+
+	pub fn field(mut self, name: &str, value: &str, mode: SearchMode) -> Self
+	{
+		self.fields.push(IndexField {
+			name: name.to_string(),
+			value: value.to_string(),
+			mode,
+			tokenize: false,
+		});
+
+		self
+	}
+
+	/// Split the value by whitespaces and hash every word in lower case.
+	pub fn tokenized_field(mut self, name: &str, value: &str, mode: SearchMode) -> Self
+	{
+		self.fields.push(IndexField {
+			name: name.to_string(),
+			value: value.to_string(),
+			mode,
+			tokenize: true,
+		});
+
+		self
+	}
+
+	pub fn build(self) -> Result<SearchIndex, SentcError>
+	{
+		let mut fields = Vec::with_capacity(self.fields.len());
+
+		for field in self.fields {
+			let hashes = if field.tokenize {
+				let mut hashes = Vec::new();
+
+				for token in tokenize(&field.value)? {
+					for hash in hash_field(self.key, &field.name, &token, field.mode)? {
+						if !hashes.contains(&hash) {
+							hashes.push(hash);
+						}
+					}
+				}
+
+				hashes
+			} else {
+				hash_field(self.key, &field.name, &field.value, field.mode)?
+			};
+
+			fields.push(SearchField {
+				name: field.name,
+				hashes,
+			});
+		}
+
+		Ok(SearchIndex {
+			key_id: self.key.get_id().to_string(),
+			alg: self.key.get_key().get_alg_str().to_string(),
+			fields,
+		})
+	}
+}
+
+/// Create the hashes to look up the fields of an index.
+///
+/// The values are matched with the full or the prefix hashes of the index.
+pub struct SearchQueryBuilder<'a, K: SearchableKeyWrapper>
+{
+	key: &'a K,
+	fields: Vec<(String, String, bool)>,
+}
+
+impl<'a, K: SearchableKeyWrapper> SearchQueryBuilder<'a, K>
+{
+	pub fn new(key: &'a K) -> Self
+	{
+		Self {
+			key,
+			fields: Vec::new(),
+		}
+	}
+
+	pub fn field(mut self, name: &str, value: &str) -> Self
+	{
+		self.fields
+			.push((name.to_string(), value.to_string(), false));
+
+		self
+	}
+
+	/// Query a tokenized field. Every word of the value got its own hash.
+	pub fn tokenized_field(mut self, name: &str, value: &str) -> Self
+	{
+		self.fields
+			.push((name.to_string(), value.to_string(), true));
+
+		self
+	}
+
+	pub fn build(self) -> Result<SearchIndex, SentcError>
+	{
+		let mut fields = Vec::with_capacity(self.fields.len());
+
+		for (name, value, tokenize_value) in self.fields {
+			let hashes = if tokenize_value {
+				tokenize(&value)?
+					.iter()
+					.map(|token| hash_value(self.key, &name, token))
+					.collect::<Result<_, _>>()?
+			} else {
+				vec![hash_value(self.key, &name, &value)?]
+			};
+
+			fields.push(SearchField {
+				name,
+				hashes,
+			});
+		}
+
+		Ok(SearchIndex {
+			key_id: self.key.get_id().to_string(),
+			alg: self.key.get_key().get_alg_str().to_string(),
+			fields,
+		})
+	}
+}
+
+//__________________________________________________________________________________________________
+
+fn tokenize(value: &str) -> Result<Vec<String>, SentcError>
+{
+	let tokens: Vec<String> = value.split_whitespace().map(str::to_lowercase).collect();
+
+	if tokens.is_empty() {
+		return Err(SdkUtilError::SearchableEncryptionDataNotFound.into());
+	}
+
+	Ok(tokens)
+}
+
+fn field_prefix(name: &str, capacity: usize) -> Vec<u8>
+{
+	//the length of the field name before the name, so no field and value can give the same bytes as another pair
+	let mut word = Vec::with_capacity(8 + name.len() + capacity);
+	word.extend_from_slice(&(name.len() as u64).to_be_bytes());
+	word.extend_from_slice(name.as_bytes());
+
+	word
+}
+
+pub(crate) fn hash_value<K: SearchableKeyWrapper>(key: &K, name: &str, value: &str) -> Result<String, SentcError>
+{
+	if value.is_empty() {
+		return Err(SdkUtilError::SearchableEncryptionDataNotFound.into());
+	}
+
+	let mut word = field_prefix(name, value.len());
+	word.extend_from_slice(value.as_bytes());
+
+	Ok(key.search_bytes(&word)?)
+}
+
+pub(crate) fn hash_field<K: SearchableKeyWrapper>(key: &K, name: &str, value: &str, mode: SearchMode) -> Result<Vec<String>, SentcError>
+{
+	match mode {
+		SearchMode::Full => Ok(vec![hash_value(key, name, value)?]),
+		SearchMode::Prefix(limit) => {
+			if value.is_empty() {
+				return Err(SdkUtilError::SearchableEncryptionDataNotFound.into());
+			}
+
+			let limit = limit.unwrap_or(value.len()).min(value.len());
+
+			if limit > 200 {
+				return Err(SdkUtilError::SearchableEncryptionDataTooLong.into());
+			}
+
+			let mut word = field_prefix(name, limit);
+			let mut hashes = Vec::with_capacity(limit);
+
+			//hash each prefix of the value, like the normal create_search
+			for byte in value.bytes().take(limit) {
+				word.push(byte);
+				hashes.push(key.search_bytes(&word)?);
+			}
+
+			Ok(hashes)
+		},
+	}
+}
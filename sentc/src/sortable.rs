@@ -0,0 +1,305 @@
+use std::ops::{Bound, RangeBounds};
+
+use sentc_crypto::sdk_core::cryptomat::CryptoAlg;
+use sentc_crypto::sdk_utils::cryptomat::SortableKeyWrapper;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SentcError;
+
+/// The sortable encryption only accepts numbers from 0 to 65532.
+/// Bigger numbers are split into digits of this base.
+const DIGIT_BASE: u64 = 65533;
+
+/// The count of digits of the base for every u64.
+const DIGIT_COUNT: usize = 5;
+
+/// The count of encrypted characters of a string if no max_len is set, the same as in the sdk.
+const DEFAULT_STRING_MAX_LEN: usize = 4;
+
+/// Order preserving encoding of a value into an u64.
+///
+/// If a < b then a.to_sortable() < b.to_sortable().
+pub trait SortableValue
+{
+	fn to_sortable(&self) -> u64;
+}
+
+impl SortableValue for u64
+{
+	fn to_sortable(&self) -> u64
+	{
+		*self
+	}
+}
+
+impl SortableValue for i64
+{
+	fn to_sortable(&self) -> u64
+	{
+		//flip the sign bit, so negative numbers are before the positive numbers
+		(*self as u64) ^ (1 << 63)
+	}
+}
+
+impl SortableValue for f64
+{
+	/// The order is the same as f64::total_cmp, -0.0 is before 0.0.
+	fn to_sortable(&self) -> u64
+	{
+		let bits = self.to_bits();
+
+		if bits >> 63 == 1 {
+			//negative numbers are in reverse order
+			!bits
+		} else {
+			bits | (1 << 63)
+		}
+	}
+}
+
+/// A fixed-point decimal with SCALE digits after the point, e.g. FixedPoint::<2>(1234) is 12.34
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct FixedPoint<const SCALE: u32>(pub i64);
+
+impl<const SCALE: u32> FixedPoint<SCALE>
+{
+	/// Create the fixed-point number from a decimal with an own scale, like 1234 with scale 2 for 12.34
+	///
+	/// Returns None if the value is too big or if digits would be lost.
+	pub fn from_parts(mantissa: i64, scale: u32) -> Option<Self>
+	{
+		if scale <= SCALE {
+			let factor = 10i64.checked_pow(SCALE - scale)?;
+
+			Some(Self(mantissa.checked_mul(factor)?))
+		} else {
+			let factor = 10i64.checked_pow(scale - SCALE)?;
+
+			if mantissa % factor != 0 {
+				return None;
+			}
+
+			Some(Self(mantissa / factor))
+		}
+	}
+}
+
+impl<const SCALE: u32> SortableValue for FixedPoint<SCALE>
+{
+	fn to_sortable(&self) -> u64
+	{
+		self.0.to_sortable()
+	}
+}
+
+/// Timestamps are sorted by the milliseconds since the unix epoch.
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> SortableValue for chrono::DateTime<Tz>
+{
+	fn to_sortable(&self) -> u64
+	{
+		self.timestamp_millis().to_sortable()
+	}
+}
+
+/// Timestamps are sorted by the milliseconds since the unix epoch.
+#[cfg(feature = "time")]
+impl SortableValue for time::OffsetDateTime
+{
+	fn to_sortable(&self) -> u64
+	{
+		//the millis of the OffsetDateTime range fit into an i64
+		((self.unix_timestamp_nanos() / 1_000_000) as i64).to_sortable()
+	}
+}
+
+//__________________________________________________________________________________________________
+
+/// The encrypted digits of a sortable value.
+///
+/// Compare the numbers one after another (like multiple order by columns) or use the sortable string.
+#[derive(Serialize, Deserialize)]
+pub struct SortableValueEncryptOutput
+{
+	pub numbers: Vec<u64>,
+	pub alg: String,
+	pub key_id: String,
+}
+
+impl SortableValueEncryptOutput
+{
+	/// All numbers as fixed length hex string. The strings got the same order as the values.
+	pub fn to_sortable_string(&self) -> String
+	{
+		to_sortable_string(&self.numbers)
+	}
+}
+
+/// Encrypt every digit of the value, beginning with the most significant digit.
+pub fn encrypt_sortable_raw_value<K: SortableKeyWrapper>(key: &K, value: &impl SortableValue) -> Result<Vec<u64>, SentcError>
+{
+	let mut number = value.to_sortable();
+	let mut digits = [0u64; DIGIT_COUNT];
+
+	for digit in digits.iter_mut().rev() {
+		*digit = number % DIGIT_BASE;
+		number /= DIGIT_BASE;
+	}
+
+	digits
+		.iter()
+		.map(|digit| Ok(key.encrypt_number_raw(*digit)?))
+		.collect()
+}
+
+pub fn encrypt_sortable_value<K: SortableKeyWrapper>(key: &K, value: &impl SortableValue) -> Result<SortableValueEncryptOutput, SentcError>
+{
+	Ok(SortableValueEncryptOutput {
+		numbers: encrypt_sortable_raw_value(key, value)?,
+		alg: key.get_key().get_alg_str().to_string(),
+		key_id: key.get_id().to_string(),
+	})
+}
+
+pub fn to_sortable_string(numbers: &[u64]) -> String
+{
+	numbers.iter().map(|n| format!("{n:016x}")).collect()
+}
+
+//__________________________________________________________________________________________________
+//range
+
+/// The encrypted bounds of a range query.
+///
+/// Use it in the backend like: column > lower (for Excluded) or column >= lower (for Included).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SortableRange
+{
+	pub lower: Bound<u64>,
+	pub upper: Bound<u64>,
+	/// If false, the range can contain values outside the plaintext range.
+	/// These values must be filtered after decryption.
+	pub exact: bool,
+}
+
+impl SortableRange
+{
+	/// Check if an encrypted value is in the range
+	pub fn contains(&self, number: u64) -> bool
+	{
+		(self.lower, self.upper).contains(&number)
+	}
+}
+
+/// Encrypt the bounds of a number range.
+///
+/// The number encryption keeps the exact order, so excluded bounds are still excluded.
+pub fn encrypt_sortable_number_range<K: SortableKeyWrapper>(key: &K, lower: Bound<u64>, upper: Bound<u64>) -> Result<SortableRange, SentcError>
+{
+	Ok(SortableRange {
+		lower: map_bound(lower, |n| Ok(key.encrypt_number_raw(n)?))?,
+		upper: map_bound(upper, |n| Ok(key.encrypt_number_raw(n)?))?,
+		exact: true,
+	})
+}
+
+/// Encrypt the bounds of a string range.
+///
+/// Only the first max_len characters are encrypted, so different strings can got the same number.
+/// Because of this, all bounds are included and the range is not exact.
+/// Use the same max_len as for the encrypted values.
+pub fn encrypt_sortable_string_range<K: SortableKeyWrapper>(
+	key: &K,
+	lower: Bound<&str>,
+	upper: Bound<&str>,
+	max_len: Option<usize>,
+) -> Result<SortableRange, SentcError>
+{
+	let encrypt = |s: &str| -> Result<u64, SentcError> { Ok(key.encrypt_raw_string(s, max_len)?) };
+
+	Ok(SortableRange {
+		lower: include_bound(map_bound(lower, |s| encrypt(&pad_lower_string(s, max_len)))?),
+		upper: include_bound(map_bound(upper, encrypt)?),
+		exact: false,
+	})
+}
+
+/// The encrypted bounds of a range query of sortable values.
+///
+/// Compare the numbers one after another or compare the sortable strings of the bounds and the values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SortableValueRange
+{
+	pub lower: Bound<Vec<u64>>,
+	pub upper: Bound<Vec<u64>>,
+}
+
+impl SortableValueRange
+{
+	/// Check if an encrypted value is in the range
+	pub fn contains(&self, numbers: &[u64]) -> bool
+	{
+		(
+			self.lower.as_ref().map(Vec::as_slice),
+			self.upper.as_ref().map(Vec::as_slice),
+		)
+			.contains(numbers)
+	}
+
+	/// The bounds as sortable strings to compare with the sortable strings of the values.
+	pub fn to_sortable_strings(&self) -> (Bound<String>, Bound<String>)
+	{
+		(
+			self.lower.as_ref().map(|n| to_sortable_string(n)),
+			self.upper.as_ref().map(|n| to_sortable_string(n)),
+		)
+	}
+}
+
+/// Encrypt the bounds of a range of signed numbers, floats, timestamps or fixed-point numbers.
+///
+/// Every digit keeps the exact order, so excluded bounds are still excluded.
+pub fn encrypt_sortable_value_range<K: SortableKeyWrapper, V: SortableValue>(
+	key: &K,
+	lower: Bound<&V>,
+	upper: Bound<&V>,
+) -> Result<SortableValueRange, SentcError>
+{
+	Ok(SortableValueRange {
+		lower: map_bound(lower, |v| encrypt_sortable_raw_value(key, v))?,
+		upper: map_bound(upper, |v| encrypt_sortable_raw_value(key, v))?,
+	})
+}
+
+/// Fill a lower bound string with the smallest character instead of the '*' of the sdk.
+///
+/// Otherwise strings with the same beginning and a character before '*' (like a space) are not in the range.
+pub(crate) fn pad_lower_string(s: &str, max_len: Option<usize>) -> String
+{
+	let max_len = max_len.unwrap_or(DEFAULT_STRING_MAX_LEN);
+
+	let mut out = s.to_string();
+
+	for _ in s.len()..max_len {
+		out.push('\0');
+	}
+
+	out
+}
+
+pub(crate) fn map_bound<T, U>(bound: Bound<T>, f: impl Fn(T) -> Result<U, SentcError>) -> Result<Bound<U>, SentcError>
+{
+	Ok(match bound {
+		Bound::Included(v) => Bound::Included(f(v)?),
+		Bound::Excluded(v) => Bound::Excluded(f(v)?),
+		Bound::Unbounded => Bound::Unbounded,
+	})
+}
+
+pub(crate) fn include_bound<T>(bound: Bound<T>) -> Bound<T>
+{
+	match bound {
+		Bound::Excluded(v) => Bound::Included(v),
+		b => b,
+	}
+}
@@ -0,0 +1,113 @@
+//! Column types for rusqlite.
+//!
+//! Create the values with the sql functions of the group, e.g. group.sql_encrypt_string(..) or group.sql_search(..).
+//! Read encrypted columns as EncryptedBlob or EncryptedText and decrypt them with the group.
+
+use rusqlite::types::{FromSql, FromSqlResult, ToSqlOutput, ValueRef};
+use rusqlite::ToSql;
+
+/// Encrypted bytes with the head, stored as BLOB.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedBlob(pub Vec<u8>);
+
+/// An encrypted string with the head, stored as TEXT.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedText(pub String);
+
+/// A searchable hash, stored as TEXT.
+///
+/// Use one row per hash for the prefix search.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchHash(pub String);
+
+/// An encrypted sortable number or string, stored as INTEGER.
+///
+/// Sqlite only got signed integers, so the sign bit is flipped to keep the order of the encrypted number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SortableNumber(pub u64);
+
+/// The encrypted digits of a sortable value (signed numbers, floats, timestamps), stored as TEXT.
+///
+/// The strings got the same order as the values.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SortableText(pub String);
+
+impl ToSql for EncryptedBlob
+{
+	fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>>
+	{
+		Ok(ToSqlOutput::Borrowed(ValueRef::Blob(&self.0)))
+	}
+}
+
+impl FromSql for EncryptedBlob
+{
+	fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self>
+	{
+		Ok(Self(value.as_blob()?.to_vec()))
+	}
+}
+
+impl ToSql for EncryptedText
+{
+	fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>>
+	{
+		self.0.to_sql()
+	}
+}
+
+impl FromSql for EncryptedText
+{
+	fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self>
+	{
+		Ok(Self(value.as_str()?.to_string()))
+	}
+}
+
+impl ToSql for SearchHash
+{
+	fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>>
+	{
+		self.0.to_sql()
+	}
+}
+
+impl FromSql for SearchHash
+{
+	fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self>
+	{
+		Ok(Self(value.as_str()?.to_string()))
+	}
+}
+
+impl ToSql for SortableNumber
+{
+	fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>>
+	{
+		Ok(ToSqlOutput::from((self.0 ^ (1 << 63)) as i64))
+	}
+}
+
+impl FromSql for SortableNumber
+{
+	fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self>
+	{
+		Ok(Self((value.as_i64()? as u64) ^ (1 << 63)))
+	}
+}
+
+impl ToSql for SortableText
+{
+	fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>>
+	{
+		self.0.to_sql()
+	}
+}
+
+impl FromSql for SortableText
+{
+	fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self>
+	{
+		Ok(Self(value.as_str()?.to_string()))
+	}
+}
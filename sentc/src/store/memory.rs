@@ -0,0 +1,143 @@
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Bound;
+
+use crate::error::SentcError;
+use crate::store::{EncryptedDocument, Storage};
+
+/// Keeps the encrypted documents and the index in memory.
+#[derive(Default)]
+pub struct MemoryStorage
+{
+	docs: HashMap<String, EncryptedDocument>,
+	/// (field, hash) -> the ids of the documents and if it is the hash of the full value
+	hashes: HashMap<(String, String), Vec<(String, bool)>>,
+	/// field -> (encrypted value, id) ordered by the value
+	sortable: HashMap<String, BTreeSet<(String, String)>>,
+}
+
+impl MemoryStorage
+{
+	pub fn new() -> Self
+	{
+		Self::default()
+	}
+
+	pub fn len(&self) -> usize
+	{
+		self.docs.len()
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		self.docs.is_empty()
+	}
+
+	fn remove_index(&mut self, doc: &EncryptedDocument)
+	{
+		for h in &doc.hashes {
+			let key = (h.field.clone(), h.hash.clone());
+
+			if let Some(ids) = self.hashes.get_mut(&key) {
+				ids.retain(|(id, _)| id != &doc.id);
+
+				if ids.is_empty() {
+					self.hashes.remove(&key);
+				}
+			}
+		}
+
+		for s in &doc.sortable {
+			if let Some(values) = self.sortable.get_mut(&s.field) {
+				values.remove(&(s.value.clone(), doc.id.clone()));
+			}
+		}
+	}
+}
+
+impl Storage for MemoryStorage
+{
+	fn put(&mut self, doc: EncryptedDocument) -> Result<(), SentcError>
+	{
+		if let Some(old) = self.docs.remove(&doc.id) {
+			self.remove_index(&old);
+		}
+
+		for h in &doc.hashes {
+			self.hashes
+				.entry((h.field.clone(), h.hash.clone()))
+				.or_default()
+				.push((doc.id.clone(), h.full));
+		}
+
+		for s in &doc.sortable {
+			self.sortable
+				.entry(s.field.clone())
+				.or_default()
+				.insert((s.value.clone(), doc.id.clone()));
+		}
+
+		self.docs.insert(doc.id.clone(), doc);
+
+		Ok(())
+	}
+
+	fn get(&self, id: &str) -> Result<Option<Vec<u8>>, SentcError>
+	{
+		Ok(self.docs.get(id).map(|doc| doc.data.clone()))
+	}
+
+	fn delete(&mut self, id: &str) -> Result<bool, SentcError>
+	{
+		match self.docs.remove(id) {
+			Some(doc) => {
+				self.remove_index(&doc);
+				Ok(true)
+			},
+			None => Ok(false),
+		}
+	}
+
+	fn find_hash(&self, field: &str, hash: &str, full_only: bool) -> Result<Vec<String>, SentcError>
+	{
+		let ids = match self.hashes.get(&(field.to_string(), hash.to_string())) {
+			Some(ids) => {
+				ids.iter()
+					.filter(|(_, full)| *full || !full_only)
+					.map(|(id, _)| id.clone())
+					.collect()
+			},
+			None => Vec::new(),
+		};
+
+		Ok(ids)
+	}
+
+	fn find_range(&self, field: &str, lower: Bound<&str>, upper: Bound<&str>) -> Result<Vec<String>, SentcError>
+	{
+		let values = match self.sortable.get(field) {
+			Some(values) => values,
+			None => return Ok(Vec::new()),
+		};
+
+		//the values are ordered by (value, id), start at the first id of the lower value
+		let start = match lower {
+			Bound::Included(l) | Bound::Excluded(l) => Bound::Included((l.to_string(), String::new())),
+			Bound::Unbounded => Bound::Unbounded,
+		};
+
+		let ids = values
+			.range((start, Bound::Unbounded))
+			.skip_while(|(value, _)| matches!(lower, Bound::Excluded(l) if value == l))
+			.take_while(|(value, _)| {
+				match upper {
+					Bound::Included(u) => value.as_str() <= u,
+					Bound::Excluded(u) => value.as_str() < u,
+					Bound::Unbounded => true,
+				}
+			})
+			.map(|(_, id)| id.clone())
+			.collect();
+
+		Ok(ids)
+	}
+}
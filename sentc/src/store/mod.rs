@@ -0,0 +1,432 @@
+//! A local store for encrypted documents of a group.
+//!
+//! The storage only gets the encrypted data, the search hashes and the encrypted sortable values.
+//! The encryption, the hashing and the decryption of the results are done with the keys of the group.
+
+mod memory;
+#[cfg(feature = "store_sqlite")]
+mod sqlite;
+
+use std::collections::HashSet;
+use std::ops::Bound;
+
+pub use memory::MemoryStorage;
+use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKeyGen};
+use sentc_crypto::sdk_utils::cryptomat::{
+	PkFromUserKeyWrapper,
+	SearchableKeyComposerWrapper,
+	SearchableKeyWrapper,
+	SignComposerWrapper,
+	SignKeyPairWrapper,
+	SortableKeyComposerWrapper,
+	SortableKeyWrapper,
+	StaticKeyComposerWrapper,
+	StaticKeyPairWrapper,
+	SymKeyComposerWrapper,
+	SymKeyGenWrapper,
+	VerifyKFromUserKeyWrapper,
+};
+#[cfg(feature = "store_sqlite")]
+pub use sqlite::SqliteStorage;
+
+use crate::error::SentcError;
+use crate::group::reindex::ReindexBatch;
+use crate::group::Group;
+use crate::searchable::{hash_field, hash_value, SearchMode};
+use crate::sortable::{include_bound, map_bound, pad_lower_string, to_sortable_string, SortableValue};
+
+/// A search hash of a field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexHash
+{
+	pub field: String,
+	pub hash: String,
+	/// True for the hash of the full value, false for the hash of a prefix.
+	pub full: bool,
+}
+
+/// An encrypted sortable value of a field.
+///
+/// The value is a hex string, the strings got the same order as the plaintext values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexSortable
+{
+	pub field: String,
+	pub value: String,
+}
+
+/// The document like it is stored: only the encrypted data and the encrypted index.
+#[derive(Clone, Debug)]
+pub struct EncryptedDocument
+{
+	pub id: String,
+	pub data: Vec<u8>,
+	pub hashes: Vec<IndexHash>,
+	pub sortable: Vec<IndexSortable>,
+}
+
+/// A backend for the encrypted documents.
+///
+/// The backend never sees any plaintext, it just compares hashes and encrypted values.
+pub trait Storage
+{
+	/// Insert the document or replace the document and its index if the id already exists.
+	fn put(&mut self, doc: EncryptedDocument) -> Result<(), SentcError>;
+
+	/// Get the encrypted data of a document.
+	fn get(&self, id: &str) -> Result<Option<Vec<u8>>, SentcError>;
+
+	/// Delete the document and its index. Returns false if the document was not found.
+	fn delete(&mut self, id: &str) -> Result<bool, SentcError>;
+
+	/// Get the ids of all documents with this hash in the field.
+	///
+	/// If full_only is true, only the hashes of full values are matched and not the prefix hashes.
+	fn find_hash(&self, field: &str, hash: &str, full_only: bool) -> Result<Vec<String>, SentcError>;
+
+	/// Get the ids of all documents with a sortable value of the field in the range, ordered by the value.
+	fn find_range(&self, field: &str, lower: Bound<&str>, upper: Bound<&str>) -> Result<Vec<String>, SentcError>;
+}
+
+//__________________________________________________________________________________________________
+
+enum SortableField
+{
+	Value(u64),
+	String(String),
+}
+
+/// A plaintext document with the fields to index.
+///
+/// The data is encrypted as it is, the fields are only used for the index.
+pub struct Document
+{
+	id: String,
+	data: Vec<u8>,
+	search: Vec<(String, String, SearchMode)>,
+	sortable: Vec<(String, SortableField)>,
+}
+
+impl Document
+{
+	pub fn new(id: &str, data: &[u8]) -> Self
+	{
+		Self {
+			id: id.to_string(),
+			data: data.to_vec(),
+			search: Vec::new(),
+			sortable: Vec::new(),
+		}
+	}
+
+	/// Index the value for find_eq (with SearchMode::Full) or for find_eq and find_prefix (with SearchMode::Prefix).
+	pub fn search(mut self, field: &str, value: &str, mode: SearchMode) -> Self
+	{
+		self.search
+			.push((field.to_string(), value.to_string(), mode));
+
+		self
+	}
+
+	/// Index a number, timestamp or any other sortable value for find_range.
+	pub fn sortable(mut self, field: &str, value: &impl SortableValue) -> Self
+	{
+		self.sortable
+			.push((field.to_string(), SortableField::Value(value.to_sortable())));
+
+		self
+	}
+
+	/// Index a string for find_string_range. Only the first 4 characters are encrypted.
+	pub fn sortable_string(mut self, field: &str, value: &str) -> Self
+	{
+		self.sortable
+			.push((field.to_string(), SortableField::String(value.to_string())));
+
+		self
+	}
+}
+
+/// A decrypted document of a query.
+pub struct StoredDocument
+{
+	pub id: String,
+	pub data: Vec<u8>,
+}
+
+//__________________________________________________________________________________________________
+
+/// Encrypt, index and query documents with the keys of a group.
+///
+/// The data is encrypted with the newest group key, the index is created with the newest hmac and sortable key.
+/// Documents of older keys can still be decrypted, but they are only found with the index of the old keys.
+pub struct EncryptedStore<'a, S, SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+where
+	S: Storage,
+	SGen: SymKeyGenWrapper,
+	StGen: StaticKeyPairWrapper,
+	SignGen: SignKeyPairWrapper,
+	SearchGen: SearchableKeyGen,
+	SortGen: SortableKeyGen,
+	SC: SymKeyComposerWrapper,
+	StC: StaticKeyComposerWrapper,
+	SignC: SignComposerWrapper,
+	SearchC: SearchableKeyComposerWrapper,
+	SortC: SortableKeyComposerWrapper,
+	PC: PkFromUserKeyWrapper,
+	VC: VerifyKFromUserKeyWrapper,
+	PwH: PwHash,
+{
+	#[allow(clippy::type_complexity)]
+	group: &'a Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+	storage: S,
+}
+
+impl<'a, S, SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+	EncryptedStore<'a, S, SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
+where
+	S: Storage,
+	SGen: SymKeyGenWrapper,
+	StGen: StaticKeyPairWrapper,
+	SignGen: SignKeyPairWrapper,
+	SearchGen: SearchableKeyGen,
+	SortGen: SortableKeyGen,
+	SC: SymKeyComposerWrapper,
+	StC: StaticKeyComposerWrapper,
+	SignC: SignComposerWrapper,
+	SearchC: SearchableKeyComposerWrapper,
+	SortC: SortableKeyComposerWrapper,
+	PC: PkFromUserKeyWrapper,
+	VC: VerifyKFromUserKeyWrapper,
+	PwH: PwHash,
+{
+	#[allow(clippy::type_complexity)]
+	pub fn new(group: &'a Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>, storage: S) -> Self
+	{
+		Self {
+			group,
+			storage,
+		}
+	}
+
+	pub fn get_storage(&self) -> &S
+	{
+		&self.storage
+	}
+
+	pub fn into_storage(self) -> S
+	{
+		self.storage
+	}
+
+	/// Encrypt and index the document. An existing document with the same id is replaced.
+	pub fn put(&mut self, doc: Document) -> Result<(), SentcError>
+	{
+		let doc = self.encrypt_document(doc)?;
+
+		self.storage.put(doc)
+	}
+
+	pub fn get(&self, id: &str) -> Result<Option<Vec<u8>>, SentcError>
+	{
+		match self.storage.get(id)? {
+			Some(data) => Ok(Some(self.group.decrypt_sync(&data, None)?)),
+			None => Ok(None),
+		}
+	}
+
+	pub fn delete(&mut self, id: &str) -> Result<bool, SentcError>
+	{
+		self.storage.delete(id)
+	}
+
+	/// Find all documents where the full value of the field is equal to the value.
+	pub fn find_eq(&self, field: &str, value: &str) -> Result<Vec<StoredDocument>, SentcError>
+	{
+		let hash = hash_value(self.group.get_newest_hmac_key(), field, value)?;
+
+		let ids = self.storage.find_hash(field, &hash, true)?;
+
+		self.fetch_documents(ids)
+	}
+
+	/// Find all documents where the value of the field starts with the prefix.
+	///
+	/// Only fields indexed with SearchMode::Prefix are found.
+	pub fn find_prefix(&self, field: &str, prefix: &str) -> Result<Vec<StoredDocument>, SentcError>
+	{
+		let hash = hash_value(self.group.get_newest_hmac_key(), field, prefix)?;
+
+		let ids = self.storage.find_hash(field, &hash, false)?;
+
+		self.fetch_documents(ids)
+	}
+
+	/// Find all documents with a sortable value of the field in the range, ordered by the value.
+	pub fn find_range<V: SortableValue>(&self, field: &str, lower: Bound<&V>, upper: Bound<&V>) -> Result<Vec<StoredDocument>, SentcError>
+	{
+		let encrypt = |value: &V| -> Result<String, SentcError> { self.encrypt_sortable(&SortableField::Value(value.to_sortable())) };
+
+		let lower = map_bound(lower, encrypt)?;
+		let upper = map_bound(upper, encrypt)?;
+
+		let ids = self.storage.find_range(
+			field,
+			lower.as_ref().map(String::as_str),
+			upper.as_ref().map(String::as_str),
+		)?;
+
+		self.fetch_documents(ids)
+	}
+
+	/// Find all documents with a sortable string of the field in the range, ordered by the string.
+	///
+	/// Only the first 4 characters are encrypted, so the bounds are always included
+	/// and the result can contain strings outside the range. Filter them after decryption.
+	pub fn find_string_range(&self, field: &str, lower: Bound<&str>, upper: Bound<&str>) -> Result<Vec<StoredDocument>, SentcError>
+	{
+		let encrypt = |value: &str| -> Result<String, SentcError> { self.encrypt_sortable(&SortableField::String(value.to_string())) };
+
+		let lower = include_bound(map_bound(lower, |s| encrypt(&pad_lower_string(s, None)))?);
+		let upper = include_bound(map_bound(upper, encrypt)?);
+
+		let ids = self.storage.find_range(
+			field,
+			lower.as_ref().map(String::as_str),
+			upper.as_ref().map(String::as_str),
+		)?;
+
+		self.fetch_documents(ids)
+	}
+
+	/// Encrypt and index the documents again with the newest keys of the group, e.g. after a key rotation.
+	///
+	/// The values of the fields are not stored, so build creates the document again from the id and the decrypted data.
+	/// Documents which are not found are skipped.
+	/// The documents are processed in batches, after each batch on_batch is called with the ids of the re-indexed documents.
+	///
+	/// Returns the count of re-indexed documents.
+	pub fn reindex<B, F>(&mut self, ids: &[String], batch_size: usize, mut build: B, mut on_batch: F) -> Result<usize, SentcError>
+	where
+		B: FnMut(&str, Vec<u8>) -> Result<Document, SentcError>,
+		F: FnMut(ReindexBatch<String>) -> Result<(), SentcError>,
+	{
+		let total = ids.len();
+		let mut done = 0;
+		let mut count = 0;
+
+		for chunk in ids.chunks(batch_size.max(1)) {
+			let mut out = Vec::with_capacity(chunk.len());
+
+			for id in chunk {
+				if let Some(data) = self.get(id)? {
+					let doc = build(id, data)?;
+					self.put(doc)?;
+
+					out.push(id.clone());
+				}
+			}
+
+			done += chunk.len();
+			count += out.len();
+
+			on_batch(ReindexBatch {
+				items: &out,
+				done,
+				total,
+				hmac_key_id: self.group.get_newest_hmac_key().get_id(),
+				sortable_key_id: self.group.get_newest_sortable_key().get_id(),
+			})?;
+		}
+
+		Ok(count)
+	}
+
+	//==============================================================================================
+	//internal fn
+
+	fn encrypt_document(&self, doc: Document) -> Result<EncryptedDocument, SentcError>
+	{
+		let key = self.group.get_newest_hmac_key();
+
+		let mut hashes = Vec::new();
+
+		for (field, value, mode) in doc.search {
+			let full = hash_value(key, &field, &value)?;
+
+			let mut field_hashes: Vec<IndexHash> = hash_field(key, &field, &value, mode)?
+				.into_iter()
+				.map(|hash| {
+					IndexHash {
+						field: field.clone(),
+						full: hash == full,
+						hash,
+					}
+				})
+				.collect();
+
+			//the prefix hashes can be limited, then the full hash is missing
+			if !field_hashes.iter().any(|h| h.full) {
+				field_hashes.push(IndexHash {
+					field: field.clone(),
+					hash: full,
+					full: true,
+				});
+			}
+
+			hashes.extend(field_hashes);
+		}
+
+		let sortable = doc
+			.sortable
+			.iter()
+			.map(|(field, value)| {
+				Ok(IndexSortable {
+					field: field.clone(),
+					value: self.encrypt_sortable(value)?,
+				})
+			})
+			.collect::<Result<_, SentcError>>()?;
+
+		Ok(EncryptedDocument {
+			data: self.group.encrypt_sync(&doc.data)?,
+			id: doc.id,
+			hashes,
+			sortable,
+		})
+	}
+
+	fn encrypt_sortable(&self, value: &SortableField) -> Result<String, SentcError>
+	{
+		match value {
+			SortableField::Value(n) => Ok(to_sortable_string(&self.group.encrypt_sortable_raw_value(n)?)),
+			SortableField::String(s) => {
+				Ok(to_sortable_string(&[self
+					.group
+					.encrypt_sortable_raw_string(s, None)?]))
+			},
+		}
+	}
+
+	fn fetch_documents(&self, ids: Vec<String>) -> Result<Vec<StoredDocument>, SentcError>
+	{
+		let mut out: Vec<StoredDocument> = Vec::with_capacity(ids.len());
+		let mut seen: HashSet<&str> = HashSet::with_capacity(ids.len());
+
+		for id in &ids {
+			//a document can match with multiple values of the same field
+			if !seen.insert(id) {
+				continue;
+			}
+
+			if let Some(data) = self.get(id)? {
+				out.push(StoredDocument {
+					id: id.clone(),
+					data,
+				});
+			}
+		}
+
+		Ok(out)
+	}
+}
@@ -0,0 +1,183 @@
+use std::ops::Bound;
+use std::path::Path;
+
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+
+use crate::error::SentcError;
+use crate::store::{EncryptedDocument, Storage};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS sentc_store_doc (
+	id TEXT PRIMARY KEY NOT NULL,
+	data BLOB NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sentc_store_hash (
+	doc_id TEXT NOT NULL,
+	field TEXT NOT NULL,
+	hash TEXT NOT NULL,
+	full INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS sentc_store_hash_lookup ON sentc_store_hash (field, hash);
+CREATE INDEX IF NOT EXISTS sentc_store_hash_doc ON sentc_store_hash (doc_id);
+CREATE TABLE IF NOT EXISTS sentc_store_sortable (
+	doc_id TEXT NOT NULL,
+	field TEXT NOT NULL,
+	value TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS sentc_store_sortable_lookup ON sentc_store_sortable (field, value);
+CREATE INDEX IF NOT EXISTS sentc_store_sortable_doc ON sentc_store_sortable (doc_id);
+";
+
+/// Stores the encrypted documents and the index in sqlite tables.
+///
+/// The tables are created if they not exists.
+pub struct SqliteStorage
+{
+	conn: Connection,
+}
+
+impl SqliteStorage
+{
+	pub fn new(conn: Connection) -> Result<Self, SentcError>
+	{
+		conn.execute_batch(SCHEMA)?;
+
+		Ok(Self {
+			conn,
+		})
+	}
+
+	pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SentcError>
+	{
+		Self::new(Connection::open(path)?)
+	}
+
+	pub fn open_in_memory() -> Result<Self, SentcError>
+	{
+		Self::new(Connection::open_in_memory()?)
+	}
+
+	pub fn get_connection(&self) -> &Connection
+	{
+		&self.conn
+	}
+}
+
+fn delete_doc(conn: &Connection, id: &str) -> Result<usize, SentcError>
+{
+	conn.execute("DELETE FROM sentc_store_hash WHERE doc_id = ?1", params![id])?;
+	conn.execute("DELETE FROM sentc_store_sortable WHERE doc_id = ?1", params![id])?;
+
+	Ok(conn.execute("DELETE FROM sentc_store_doc WHERE id = ?1", params![id])?)
+}
+
+impl Storage for SqliteStorage
+{
+	fn put(&mut self, doc: EncryptedDocument) -> Result<(), SentcError>
+	{
+		let tx = self.conn.transaction()?;
+
+		delete_doc(&tx, &doc.id)?;
+
+		tx.execute(
+			"INSERT INTO sentc_store_doc (id, data) VALUES (?1, ?2)",
+			params![doc.id, doc.data],
+		)?;
+
+		for h in &doc.hashes {
+			tx.execute(
+				"INSERT INTO sentc_store_hash (doc_id, field, hash, full) VALUES (?1, ?2, ?3, ?4)",
+				params![doc.id, h.field, h.hash, h.full],
+			)?;
+		}
+
+		for s in &doc.sortable {
+			tx.execute(
+				"INSERT INTO sentc_store_sortable (doc_id, field, value) VALUES (?1, ?2, ?3)",
+				params![doc.id, s.field, s.value],
+			)?;
+		}
+
+		tx.commit()?;
+
+		Ok(())
+	}
+
+	fn get(&self, id: &str) -> Result<Option<Vec<u8>>, SentcError>
+	{
+		Ok(self
+			.conn
+			.query_row("SELECT data FROM sentc_store_doc WHERE id = ?1", params![id], |row| {
+				row.get(0)
+			})
+			.optional()?)
+	}
+
+	fn delete(&mut self, id: &str) -> Result<bool, SentcError>
+	{
+		let tx = self.conn.transaction()?;
+
+		let deleted = delete_doc(&tx, id)?;
+
+		tx.commit()?;
+
+		Ok(deleted > 0)
+	}
+
+	fn find_hash(&self, field: &str, hash: &str, full_only: bool) -> Result<Vec<String>, SentcError>
+	{
+		let sql = if full_only {
+			"SELECT DISTINCT doc_id FROM sentc_store_hash WHERE field = ?1 AND hash = ?2 AND full = 1"
+		} else {
+			"SELECT DISTINCT doc_id FROM sentc_store_hash WHERE field = ?1 AND hash = ?2"
+		};
+
+		let mut stmt = self.conn.prepare(sql)?;
+
+		let ids: Vec<String> = stmt
+			.query_map(params![field, hash], |row| row.get(0))?
+			.collect::<Result<_, _>>()?;
+
+		Ok(ids)
+	}
+
+	fn find_range(&self, field: &str, lower: Bound<&str>, upper: Bound<&str>) -> Result<Vec<String>, SentcError>
+	{
+		let mut sql = String::from("SELECT doc_id FROM sentc_store_sortable WHERE field = ?");
+		let mut values = vec![field];
+
+		match lower {
+			Bound::Included(v) => {
+				sql.push_str(" AND value >= ?");
+				values.push(v);
+			},
+			Bound::Excluded(v) => {
+				sql.push_str(" AND value > ?");
+				values.push(v);
+			},
+			Bound::Unbounded => {},
+		}
+
+		match upper {
+			Bound::Included(v) => {
+				sql.push_str(" AND value <= ?");
+				values.push(v);
+			},
+			Bound::Excluded(v) => {
+				sql.push_str(" AND value < ?");
+				values.push(v);
+			},
+			Bound::Unbounded => {},
+		}
+
+		sql.push_str(" ORDER BY value, doc_id");
+
+		let mut stmt = self.conn.prepare(&sql)?;
+
+		let ids: Vec<String> = stmt
+			.query_map(params_from_iter(values), |row| row.get(0))?
+			.collect::<Result<_, _>>()?;
+
+		Ok(ids)
+	}
+}
@@ -1,25 +1,50 @@
-use sentc_crypto::crypto::{split_head_and_encrypted_data, split_head_and_encrypted_string, KeyGenerator};
-use sentc_crypto::sdk_common::crypto::EncryptedHead;
+use sentc_crypto::crypto::{split_head_and_encrypted_data, KeyGenerator};
+use sentc_crypto::sdk_common::crypto::{EncryptedHead, SignHead};
 use sentc_crypto::sdk_common::user::{UserPublicKeyData, UserVerifyKeyData};
-use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKeyGen};
+use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKeyGen, SymKeyGen, VerifyK};
 use sentc_crypto::sdk_utils::cryptomat::{
+	KeyToString,
 	PkFromUserKeyWrapper,
 	SearchableKeyComposerWrapper,
 	SignComposerWrapper,
+	SignKCryptoWrapper,
 	SignKeyPairWrapper,
 	SkCryptoWrapper,
 	SortableKeyComposerWrapper,
 	StaticKeyComposerWrapper,
 	StaticKeyPairWrapper,
 	SymKeyComposerWrapper,
+	SymKeyCrypto,
 	SymKeyGenWrapper,
 	VerifyKFromUserKeyWrapper,
 };
+use sentc_crypto::sdk_utils::error::SdkUtilError;
+use sentc_crypto::sdk_utils::put_head_and_encrypted_data;
+use serde::{Deserialize, Serialize};
 
 use crate::crypto_common::crypto::GeneratedSymKeyHeadServerOutput;
+use crate::encrypt_options::{apply_options, decode_string, decrypted_to_string, encode_string, undo_options, EncryptOptions, OptionsEncryptedHead};
 use crate::error::SentcError;
 use crate::user::User;
 
+/// The encrypted symmetric key of a multi-recipient envelope for one recipient.
+#[derive(Serialize, Deserialize)]
+pub struct MultiEncryptedKey
+{
+	/// The id of the public key which was used to encrypt the symmetric key
+	pub id: String,
+	pub encrypted_key: String,
+}
+
+/// The head of a multi-recipient envelope.
+///
+/// The data is encrypted once with a new symmetric key and this key is encrypted for every recipient.
+#[derive(Serialize, Deserialize)]
+pub struct MultiEncryptedHead
+{
+	pub keys: Vec<MultiEncryptedKey>,
+}
+
 impl<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
 	User<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
 where
@@ -46,7 +71,7 @@ where
 
 			Ok(PC::encrypt_raw_with_user_key_with_sign(reply_key, data, sign_key)?)
 		} else {
-			Ok(PC::encrypt_raw_with_user_key(reply_key, data)?)
+			encrypt_raw_for_user::<PC>(data, reply_key)
 		}
 	}
 
@@ -72,15 +97,37 @@ where
 
 			Ok(PC::encrypt_with_user_key_with_sign(reply_key, data, sign_key)?)
 		} else {
-			Ok(PC::encrypt_with_user_key(reply_key, data)?)
+			encrypt_for_user::<PC>(data, reply_key)
 		}
 	}
 
 	pub fn decrypt_sync(&self, data: &[u8], verify_key: Option<&UserVerifyKeyData>) -> Result<Vec<u8>, SentcError>
 	{
-		let (head, data) = split_head_and_encrypted_data(data)?;
+		let (head, data): (OptionsEncryptedHead, _) = split_head_and_encrypted_data(data)?;
+
+		let decrypted = self.decrypt_raw_sync(&head.head, data, verify_key)?;
+
+		undo_options(decrypted, &head)
+	}
+
+	//______________________________________________________________________________________________
+	//encrypt with options
+
+	/// Compress and/or pad the data before encryption. Decrypt it with decrypt_sync.
+	pub fn encrypt_with_options_sync(
+		&self,
+		data: &[u8],
+		reply_key: &UserPublicKeyData,
+		sign: bool,
+		options: &EncryptOptions,
+	) -> Result<Vec<u8>, SentcError>
+	{
+		let (head, encrypted) = self.encrypt_raw_sync(&apply_options(data, options)?, reply_key, sign)?;
 
-		self.decrypt_raw_sync(&head, data, verify_key)
+		Ok(put_head_and_encrypted_data(
+			&OptionsEncryptedHead::new(head, options),
+			&encrypted,
+		)?)
 	}
 
 	//______________________________________________________________________________________________
@@ -93,19 +140,102 @@ where
 
 			Ok(PC::encrypt_string_with_user_key_with_sign(reply_key, data, sign_key)?)
 		} else {
-			Ok(PC::encrypt_string_with_user_key(reply_key, data)?)
+			encrypt_string_for_user::<PC>(data, reply_key)
 		}
 	}
 
 	pub fn decrypt_string_sync(&self, data: &str, verify_key: Option<&UserVerifyKeyData>) -> Result<String, SentcError>
 	{
-		let head = split_head_and_encrypted_string(data)?;
+		//decrypt it like the bytes to undo the options if the string was encrypted with options
+		let decrypted = self.decrypt_sync(&decode_string(data)?, verify_key)?;
 
-		let key = self
-			.get_user_keys(&head.id)
+		decrypted_to_string(decrypted)
+	}
+
+	/// Compress and/or pad the string before encryption. Decrypt it with decrypt_string_sync.
+	pub fn encrypt_string_with_options_sync(
+		&self,
+		data: &str,
+		reply_key: &UserPublicKeyData,
+		sign: bool,
+		options: &EncryptOptions,
+	) -> Result<String, SentcError>
+	{
+		Ok(encode_string(&self.encrypt_with_options_sync(
+			data.as_bytes(),
+			reply_key,
+			sign,
+			options,
+		)?))
+	}
+
+	//______________________________________________________________________________________________
+	//sign
+
+	/// Sign the data with the newest sign key of the user without encrypting it.
+	///
+	/// Returns the sign head (the id and the alg of the sign key) and only the signature, not the data.
+	/// The verify key can be fetched with the key id of the sign head.
+	pub fn sign_detached(&self, data: &[u8]) -> Result<(SignHead, Vec<u8>), SentcError>
+	{
+		let sign_key = self.get_newest_sign_key().ok_or(SentcError::KeyNotFound)?;
+
+		let (head, data_with_sig) = sign_key.sign_with_head(data)?;
+
+		let (sig, _) = VC::split_sig_and_data(&head.alg, &data_with_sig)?;
+
+		Ok((head, sig.to_vec()))
+	}
+
+	/// Verify a signature from sign_detached with the verify key of the user.
+	pub fn verify_detached(data: &[u8], signature: &[u8], verify_key: &UserVerifyKeyData) -> Result<bool, SentcError>
+	{
+		let vk = VC::from_user_key(verify_key)?;
+
+		let mut data_with_sig = Vec::with_capacity(signature.len() + data.len());
+		data_with_sig.extend_from_slice(signature);
+		data_with_sig.extend_from_slice(data);
+
+		let (_, check) = vk.verify(&data_with_sig)?;
+
+		Ok(check)
+	}
+
+	//______________________________________________________________________________________________
+	//encrypt for multiple users
+
+	/// Encrypt the data once for all users (or groups) of the public keys.
+	///
+	/// Add the own public key to the reply keys to decrypt the data later again.
+	pub fn encrypt_for_users_sync(&self, data: &[u8], reply_keys: &[UserPublicKeyData], sign: bool) -> Result<Vec<u8>, SentcError>
+	{
+		let (head, key) = prepare_encrypt_for_users::<SGen, PC>(reply_keys)?;
+
+		let encrypted = if sign {
+			let sign_key = self.get_newest_sign_key().ok_or(SentcError::KeyNotFound)?;
+
+			key.encrypt_with_sign(data, sign_key)?
+		} else {
+			key.encrypt(data)?
+		};
+
+		Ok(put_head_and_encrypted_data(&head, &encrypted)?)
+	}
+
+	/// Decrypt data from encrypt_for_users with the key of this user.
+	pub fn decrypt_for_users_sync(&self, data: &[u8], verify_key: Option<&UserVerifyKeyData>) -> Result<Vec<u8>, SentcError>
+	{
+		let (head, encrypted): (MultiEncryptedHead, _) = split_head_and_encrypted_data(data)?;
+
+		let (entry, user_key) = head
+			.keys
+			.iter()
+			.find_map(|k| self.get_user_keys(&k.id).map(|user_key| (k, user_key)))
 			.ok_or(SentcError::KeyNotFound)?;
 
-		Ok(key.private_key.decrypt_string(data, verify_key)?)
+		let key = decrypt_multi_encrypted_key::<SC>(&user_key.private_key, entry)?;
+
+		Ok(key.decrypt(encrypted, verify_key)?)
 	}
 
 	//==============================================================================================
@@ -134,3 +264,80 @@ where
 		)?)
 	}
 }
+
+//__________________________________________________________________________________________________
+//encrypt without a user
+
+/// Encrypt raw data for a user without being logged in.
+///
+/// The public key of the other user can be fetched with net_helper::get_user_public_key_data.
+/// The data is not signed because there is no sign key without a user.
+pub fn encrypt_raw_for_user<PC: PkFromUserKeyWrapper>(data: &[u8], reply_key: &UserPublicKeyData) -> Result<(EncryptedHead, Vec<u8>), SentcError>
+{
+	Ok(PC::encrypt_raw_with_user_key(reply_key, data)?)
+}
+
+/// Encrypt data for a user without being logged in.
+pub fn encrypt_for_user<PC: PkFromUserKeyWrapper>(data: &[u8], reply_key: &UserPublicKeyData) -> Result<Vec<u8>, SentcError>
+{
+	Ok(PC::encrypt_with_user_key(reply_key, data)?)
+}
+
+/// Encrypt a string for a user without being logged in.
+pub fn encrypt_string_for_user<PC: PkFromUserKeyWrapper>(data: &str, reply_key: &UserPublicKeyData) -> Result<String, SentcError>
+{
+	Ok(PC::encrypt_string_with_user_key(reply_key, data)?)
+}
+
+/// Encrypt the data once for all users (or groups) of the public keys without being logged in.
+///
+/// Every recipient can decrypt the data with decrypt_for_users_sync.
+pub fn encrypt_for_users<SGen: SymKeyGenWrapper, PC: PkFromUserKeyWrapper>(
+	data: &[u8],
+	reply_keys: &[UserPublicKeyData],
+) -> Result<Vec<u8>, SentcError>
+{
+	let (head, key) = prepare_encrypt_for_users::<SGen, PC>(reply_keys)?;
+
+	let encrypted = key.encrypt(data)?;
+
+	Ok(put_head_and_encrypted_data(&head, &encrypted)?)
+}
+
+fn prepare_encrypt_for_users<SGen: SymKeyGenWrapper, PC: PkFromUserKeyWrapper>(
+	reply_keys: &[UserPublicKeyData],
+) -> Result<(MultiEncryptedHead, SGen::SymmetricKeyWrapper), SentcError>
+{
+	let key = SGen::from_inner(SGen::KeyGen::generate()?, "non_registered".to_string());
+
+	let exported_key = key.to_string_ref()?;
+
+	let keys = reply_keys
+		.iter()
+		.map(|reply_key| {
+			Ok(MultiEncryptedKey {
+				id: reply_key.public_key_id.clone(),
+				encrypted_key: PC::encrypt_string_with_user_key(reply_key, &exported_key)?,
+			})
+		})
+		.collect::<Result<_, SdkUtilError>>()?;
+
+	Ok((
+		MultiEncryptedHead {
+			keys,
+		},
+		key,
+	))
+}
+
+pub(crate) fn decrypt_multi_encrypted_key<SC: SymKeyComposerWrapper>(
+	private_key: &impl SkCryptoWrapper,
+	entry: &MultiEncryptedKey,
+) -> Result<SC::SymmetricKeyWrapper, SentcError>
+{
+	let exported_key = private_key.decrypt_string(&entry.encrypted_key, None)?;
+
+	Ok(exported_key
+		.parse()
+		.map_err(|_| SdkUtilError::ImportingKeyFromPemFailed)?)
+}
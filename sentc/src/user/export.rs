@@ -40,6 +40,10 @@ pub struct UserExportData
 	user_keys: Vec<UserKeyDataExport>,
 	device_keys: DeviceKeyDataExport,
 	hmac_keys: Vec<String>,
+	#[serde(default)]
+	sortable_keys: Vec<String>,
+	#[serde(default)]
+	group_key_limit: Option<usize>,
 }
 
 impl<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>
@@ -90,6 +94,12 @@ where
 				.into_iter()
 				.map(|k| k.to_string())
 				.collect::<Result<_, SdkUtilError>>()?,
+			sortable_keys: value
+				.sortable_keys
+				.into_iter()
+				.map(|k| k.to_string())
+				.collect::<Result<_, SdkUtilError>>()?,
+			group_key_limit: value.group_key_limit,
 		})
 	}
 }
@@ -142,6 +152,12 @@ where
 				.iter()
 				.map(|k| k.to_string_ref())
 				.collect::<Result<_, SdkUtilError>>()?,
+			sortable_keys: value
+				.sortable_keys
+				.iter()
+				.map(|k| k.to_string_ref())
+				.collect::<Result<_, SdkUtilError>>()?,
+			group_key_limit: value.group_key_limit,
 		})
 	}
 }
@@ -222,6 +238,17 @@ where
 			})
 			.collect::<Result<_, SdkUtilError>>()?;
 
+		user.sortable_keys = self
+			.sortable_keys
+			.into_iter()
+			.map(|k| {
+				k.parse()
+					.map_err(|_| SdkUtilError::ImportingKeyFromPemFailed)
+			})
+			.collect::<Result<_, SdkUtilError>>()?;
+
+		user.group_key_limit = self.group_key_limit;
+
 		Ok(user)
 	}
 }
@@ -9,20 +9,27 @@ pub mod file;
 #[cfg(feature = "network")]
 pub mod net;
 
+#[cfg(feature = "network")]
+use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::ops::Bound;
 
 use sentc_crypto::entities::user::{UserDataInt, UserKeyDataInt};
 use sentc_crypto::group::Group as SdkGroup;
-use sentc_crypto::sdk_common::group::GroupHmacData;
+use sentc_crypto::sdk_common::content_searchable::SearchableCreateOutput;
+use sentc_crypto::sdk_common::content_sortable::SortableEncryptOutput;
+use sentc_crypto::sdk_common::group::{GroupHmacData, GroupSortableData};
 use sentc_crypto::sdk_common::user::{UserPublicKeyData, UserVerifyKeyData};
 use sentc_crypto::sdk_common::{DeviceId, SymKeyId, UserId};
-use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKeyGen};
+use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKey as CoreSort, SortableKeyGen};
 use sentc_crypto::sdk_utils::cryptomat::{
 	PkFromUserKeyWrapper,
 	SearchableKeyComposerWrapper,
+	SearchableKeyWrapper,
 	SignComposerWrapper,
 	SignKeyPairWrapper,
 	SortableKeyComposerWrapper,
+	SortableKeyWrapper,
 	StaticKeyComposerWrapper,
 	StaticKeyPairWrapper,
 	SymKeyComposerWrapper,
@@ -34,6 +41,17 @@ use sentc_crypto::user::{generate_user_register_data, User as SdkUser};
 
 use crate::error::SentcError;
 use crate::group::prepare_group_keys_ref;
+use crate::sortable::{
+	encrypt_sortable_number_range,
+	encrypt_sortable_raw_value,
+	encrypt_sortable_string_range,
+	encrypt_sortable_value,
+	encrypt_sortable_value_range,
+	SortableRange,
+	SortableValue,
+	SortableValueEncryptOutput,
+	SortableValueRange,
+};
 use crate::KeyMap;
 
 /// The user struct holds all information about the user.
@@ -78,6 +96,11 @@ where
 	key_map: KeyMap,
 	newest_key_id: SymKeyId,
 	hmac_keys: Vec<SearchC::SearchableKeyWrapper>,
+	sortable_keys: Vec<SortC::SortableKeyWrapper>,
+	#[cfg(feature = "network")]
+	non_registered_keys: HashMap<String, SC::SymmetricKeyWrapper>,
+	/// Only load the newest keys of a group when fetching the group.
+	group_key_limit: Option<usize>,
 
 	base_url: String,
 	app_token: String,
@@ -155,6 +178,10 @@ where
 				key_map,
 				newest_key_id,
 				hmac_keys: Vec::with_capacity(data.hmac_keys.len()),
+				sortable_keys: Vec::new(),
+				#[cfg(feature = "network")]
+				non_registered_keys: Default::default(),
+				group_key_limit: None,
 				base_url,
 				app_token,
 
@@ -243,6 +270,29 @@ where
 		self.get_newest_key().map(|k| &k.sign_key)
 	}
 
+	pub fn get_newest_hmac_key(&self) -> Option<&SearchC::SearchableKeyWrapper>
+	{
+		self.hmac_keys.first()
+	}
+
+	pub fn get_newest_sortable_key(&self) -> Option<&SortC::SortableKeyWrapper>
+	{
+		self.sortable_keys.first()
+	}
+
+	pub fn get_group_key_limit(&self) -> Option<usize>
+	{
+		self.group_key_limit
+	}
+
+	/// Load only the newest keys of a group when fetching the group.
+	///
+	/// The older keys can be fetched when they are needed. Child and connected groups are fetched with the same limit.
+	pub fn set_group_key_limit(&mut self, limit: Option<usize>)
+	{
+		self.group_key_limit = limit;
+	}
+
 	pub fn set_jwt(&mut self, jwt: String)
 	{
 		self.jwt = jwt;
@@ -365,6 +415,24 @@ where
 		Ok(())
 	}
 
+	#[allow(clippy::type_complexity)]
+	pub fn set_sortable_key(
+		&mut self,
+		user_key: &UserKeyDataInt<SC::SymmetricKeyWrapper, StC::SkWrapper, StC::PkWrapper, SignC::SignKWrapper, SignC::VerifyKWrapper>,
+		sortable_key: GroupSortableData,
+	) -> Result<(), SentcError>
+	{
+		let decrypted_sortable_key =
+			SdkGroup::<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC>::decrypt_group_sortable_key(
+				&user_key.group_key,
+				sortable_key,
+			)?;
+
+		self.sortable_keys.push(decrypted_sortable_key);
+
+		Ok(())
+	}
+
 	pub fn prepare_create_group(&self, sign: bool) -> Result<String, SentcError>
 	{
 		let sign_key = if sign { self.get_newest_sign_key() } else { None };
@@ -417,6 +485,135 @@ where
 		)?)
 	}
 
+	//______________________________________________________________________________________________
+	//searchable encryption
+
+	pub fn create_search_raw(&self, data: &str, full: bool, limit: Option<usize>) -> Result<Vec<String>, SentcError>
+	{
+		let key = self.get_newest_hmac_key().ok_or(SentcError::KeyNotFound)?;
+
+		Ok(key.create_searchable_raw(data, full, limit)?)
+	}
+
+	pub fn create_search(&self, data: &str, full: bool, limit: Option<usize>) -> Result<SearchableCreateOutput, SentcError>
+	{
+		let key = self.get_newest_hmac_key().ok_or(SentcError::KeyNotFound)?;
+
+		Ok(key.create_searchable(data, full, limit)?)
+	}
+
+	pub fn search(&self, data: &str) -> Result<String, SentcError>
+	{
+		let key = self.get_newest_hmac_key().ok_or(SentcError::KeyNotFound)?;
+
+		Ok(key.search(data)?)
+	}
+
+	/// Create the search hash with every hmac key, not only with the newest key.
+	///
+	/// Returns the key id and the hash. Use the key id of create_search to match the hashes of the right key.
+	pub fn search_all(&self, data: &str) -> Result<Vec<(SymKeyId, String)>, SentcError>
+	{
+		self.hmac_keys
+			.iter()
+			.map(|key| Ok((key.get_id().to_string(), key.search(data)?)))
+			.collect()
+	}
+
+	//______________________________________________________________________________________________
+	//sortable
+
+	pub fn encrypt_sortable_raw_number(&self, number: u64) -> Result<u64, SentcError>
+	{
+		let key = self
+			.get_newest_sortable_key()
+			.ok_or(SentcError::KeyNotFound)?;
+
+		Ok(key.get_key().encrypt_sortable(number)?)
+	}
+
+	pub fn encrypt_sortable_number(&self, number: u64) -> Result<SortableEncryptOutput, SentcError>
+	{
+		let key = self
+			.get_newest_sortable_key()
+			.ok_or(SentcError::KeyNotFound)?;
+
+		Ok(key.encrypt_number(number)?)
+	}
+
+	pub fn encrypt_sortable_raw_string(&self, data: &str, max_len: Option<usize>) -> Result<u64, SentcError>
+	{
+		let key = self
+			.get_newest_sortable_key()
+			.ok_or(SentcError::KeyNotFound)?;
+
+		Ok(key.encrypt_raw_string(data, max_len)?)
+	}
+
+	pub fn encrypt_sortable_string(&self, data: &str, max_len: Option<usize>) -> Result<SortableEncryptOutput, SentcError>
+	{
+		let key = self
+			.get_newest_sortable_key()
+			.ok_or(SentcError::KeyNotFound)?;
+
+		Ok(key.encrypt_string(data, max_len)?)
+	}
+
+	/// Encrypt signed numbers, floats, timestamps or fixed-point numbers.
+	///
+	/// The value is split into multiple numbers. Compare them one after another.
+	pub fn encrypt_sortable_raw_value(&self, value: &impl SortableValue) -> Result<Vec<u64>, SentcError>
+	{
+		let key = self
+			.get_newest_sortable_key()
+			.ok_or(SentcError::KeyNotFound)?;
+
+		encrypt_sortable_raw_value(key, value)
+	}
+
+	pub fn encrypt_sortable_value(&self, value: &impl SortableValue) -> Result<SortableValueEncryptOutput, SentcError>
+	{
+		let key = self
+			.get_newest_sortable_key()
+			.ok_or(SentcError::KeyNotFound)?;
+
+		encrypt_sortable_value(key, value)
+	}
+
+	/// Get the encrypted bounds for a range query of encrypted numbers.
+	pub fn encrypt_sortable_number_range(&self, lower: Bound<u64>, upper: Bound<u64>) -> Result<SortableRange, SentcError>
+	{
+		let key = self
+			.get_newest_sortable_key()
+			.ok_or(SentcError::KeyNotFound)?;
+
+		encrypt_sortable_number_range(key, lower, upper)
+	}
+
+	/// Get the encrypted bounds for a range query of encrypted strings.
+	///
+	/// The range is not exact, filter the values after decryption.
+	pub fn encrypt_sortable_string_range(&self, lower: Bound<&str>, upper: Bound<&str>, max_len: Option<usize>) -> Result<SortableRange, SentcError>
+	{
+		let key = self
+			.get_newest_sortable_key()
+			.ok_or(SentcError::KeyNotFound)?;
+
+		encrypt_sortable_string_range(key, lower, upper, max_len)
+	}
+
+	/// Get the encrypted bounds for a range query of encrypted signed numbers, floats, timestamps or fixed-point numbers.
+	pub fn encrypt_sortable_value_range<V: SortableValue>(&self, lower: Bound<&V>, upper: Bound<&V>) -> Result<SortableValueRange, SentcError>
+	{
+		let key = self
+			.get_newest_sortable_key()
+			.ok_or(SentcError::KeyNotFound)?;
+
+		encrypt_sortable_value_range(key, lower, upper)
+	}
+
+	//______________________________________________________________________________________________
+
 	pub fn set_newest_key_id(&mut self, id: SymKeyId)
 	{
 		self.newest_key_id = id;
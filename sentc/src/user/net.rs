@@ -1,7 +1,9 @@
+use futures_util::Stream;
+use sentc_crypto::crypto::KeyGenerator;
 use sentc_crypto::entities::group::GroupOutData;
 use sentc_crypto::entities::user::UserDataInt;
 use sentc_crypto::group::Group as SdkGroup;
-use sentc_crypto::sdk_common::group::{GroupHmacData, GroupInviteReqList, ListGroups};
+use sentc_crypto::sdk_common::group::{GroupHmacData, GroupInviteReqList, GroupSortableData, ListGroups};
 use sentc_crypto::sdk_common::user::{OtpRegister, UserDeviceList};
 use sentc_crypto::sdk_common::GroupId;
 use sentc_crypto::sdk_core::cryptomat::{PwHash, SearchableKeyGen, SortableKeyGen};
@@ -23,6 +25,7 @@ use sentc_crypto::user::User as SdkUser;
 use sentc_crypto::util_req_full::group::{
 	accept_invite,
 	delete_sent_join_req,
+	get_group,
 	get_groups_for_user,
 	get_invites_for_user,
 	get_sent_join_req,
@@ -46,11 +49,12 @@ use sentc_crypto::util_req_full::user::{
 	PreLoginOut,
 };
 
+use crate::crypto_common::crypto::GeneratedSymKeyHeadServerOutput;
 use crate::crypto_common::user::{UserPublicKeyData, UserVerifyKeyData};
 use crate::error::SentcError;
-use crate::group::net::GroupFetchResult;
+use crate::group::net::{GroupFetchResult, GroupKeyVerifyMode, GroupKeyVerifyWarning};
 use crate::group::{Group, GroupKeyVerifyKeys};
-use crate::net_helper::{check_jwt, get_user_verify_key_data};
+use crate::net_helper::{check_jwt, get_user_verify_key_data, list_stream};
 use crate::user::User;
 
 #[allow(clippy::large_enum_variant)]
@@ -253,6 +257,7 @@ where
 			Some(self),
 			group_as_member,
 			false,
+			self.group_key_limit,
 		)
 		.await
 	}
@@ -273,9 +278,59 @@ where
 			Some(self),
 			group_as_member,
 			verify_keys,
+			self.group_key_limit,
 		)
 	}
 
+	/// Fetch a group in one call.
+	///
+	/// Returns an error if a user key or a key of the group as member to decrypt the group keys is not loaded.
+	#[allow(clippy::type_complexity)]
+	pub async fn get_group(
+		&self,
+		group_id: &str,
+		group_as_member: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+	) -> Result<Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>, SentcError>
+	{
+		let (data, res) = self.prepare_get_group(group_id, group_as_member).await?;
+
+		res.into_result()?;
+
+		self.done_get_group(data, group_as_member, None)
+	}
+
+	/// Like done_get_group but every signed group key is verified with the verify key of the signer.
+	///
+	/// The verify keys are fetched from the server.
+	/// With GroupKeyVerifyMode::Warn, unsigned keys and keys with an invalid signature are loaded too
+	/// and returned as warnings.
+	#[allow(clippy::type_complexity)]
+	pub async fn done_get_group_verified(
+		&self,
+		data: GroupOutData,
+		group_as_member: Option<&Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>>,
+		mode: GroupKeyVerifyMode,
+	) -> Result<
+		(
+			Group<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>,
+			Vec<GroupKeyVerifyWarning>,
+		),
+		SentcError,
+	>
+	{
+		Group::<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC, PwH>::done_fetch_group_verified(
+			self.base_url.clone(),
+			self.app_token.clone(),
+			false,
+			data,
+			Some(self),
+			group_as_member,
+			self.group_key_limit,
+			mode,
+		)
+		.await
+	}
+
 	pub async fn create_group(&self, sign: bool) -> Result<GroupId, SentcError>
 	{
 		check_jwt(&self.jwt)?;
@@ -320,6 +375,18 @@ where
 		.await?)
 	}
 
+	/// Like get_groups but fetches all pages.
+	pub fn get_groups_stream<'a>(&'a self) -> impl Stream<Item = Result<ListGroups, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_groups(pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn get_group_invites(&self, last_item: Option<&GroupInviteReqList>) -> Result<Vec<GroupInviteReqList>, SentcError>
 	{
 		check_jwt(&self.jwt)?;
@@ -342,6 +409,18 @@ where
 		.await?)
 	}
 
+	/// Like get_group_invites but fetches all pages.
+	pub fn get_group_invites_stream<'a>(&'a self) -> impl Stream<Item = Result<GroupInviteReqList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_group_invites(pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	pub async fn accept_group_invite(&self, group_id_to_accept: &str) -> Result<(), SentcError>
 	{
 		check_jwt(&self.jwt)?;
@@ -426,6 +505,18 @@ where
 		.await?)
 	}
 
+	/// Like get_sent_join_req but fetches all pages.
+	pub fn get_sent_join_req_stream<'a>(&'a self) -> impl Stream<Item = Result<GroupInviteReqList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_sent_join_req(pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	//==============================================================================================
 	//otp
 
@@ -668,6 +759,18 @@ where
 		.await?)
 	}
 
+	/// Like get_devices but fetches all pages.
+	pub fn get_devices_stream<'a>(&'a self) -> impl Stream<Item = Result<UserDeviceList, SentcError>> + 'a
+	{
+		list_stream(move |pages| {
+			async move {
+				let page = self.get_devices(pages.last_item()).await?;
+
+				Ok((pages, page))
+			}
+		})
+	}
+
 	//==============================================================================================
 
 	pub async fn key_rotation(&mut self) -> Result<(), SentcError>
@@ -791,6 +894,44 @@ where
 
 	//==============================================================================================
 
+	/// Get a non-registered key from its encrypted key blob (the exported GeneratedSymKeyHeadServerOutput).
+	///
+	/// If the user key which encrypted the key is not loaded yet, it will be fetched first.
+	/// Decrypted keys are cached for this user instance.
+	pub async fn get_non_registered_key(&mut self, server_output: &str) -> Result<&SC::SymmetricKeyWrapper, SentcError>
+	{
+		let out = GeneratedSymKeyHeadServerOutput::from_string(server_output)?;
+
+		//non registered keys got all the same key id, use the encrypted key instead
+		if self
+			.non_registered_keys
+			.contains_key(&out.encrypted_key_string)
+		{
+			return self
+				.non_registered_keys
+				.get(&out.encrypted_key_string)
+				.ok_or(SentcError::KeyNotFound);
+		}
+
+		if self.has_user_keys(&out.master_key_id).is_none() {
+			self.fetch_user_key_internally(&out.master_key_id, false)
+				.await?;
+		}
+
+		let master_key = self
+			.get_user_keys(&out.master_key_id)
+			.ok_or(SentcError::KeyNotFound)?;
+
+		let key = KeyGenerator::<SGen, SC, PC>::decrypt_sym_key_by_private_key(&master_key.private_key, &out)?;
+
+		Ok(self
+			.non_registered_keys
+			.entry(out.encrypted_key_string)
+			.or_insert(key))
+	}
+
+	//==============================================================================================
+
 	#[allow(clippy::type_complexity)]
 	pub(crate) async fn set_user(
 		base_url: &str,
@@ -813,9 +954,53 @@ where
 			u.decrypt_hmac_key(hmac_key).await?;
 		}
 
+		//the sortable keys are not in the login output. the login should not fail without them,
+		//the keys can be fetched later again with fetch_sortable_keys
+		let _ = u.fetch_sortable_keys().await;
+
 		Ok(u)
 	}
 
+	/// Fetch and decrypt the sortable keys of the user group.
+	///
+	/// The keys are not part of the login output, they are fetched at login and exported with the user.
+	/// Call this again if the keys could not be fetched at login.
+	pub async fn fetch_sortable_keys(&mut self) -> Result<(), SentcError>
+	{
+		check_jwt(&self.jwt)?;
+
+		let out = get_group(self.base_url.clone(), &self.app_token, &self.jwt, &self.user_id, None).await?;
+
+		self.sortable_keys = Vec::with_capacity(out.sortable_keys.len());
+
+		for sortable_key in out.sortable_keys {
+			self.decrypt_sortable_key(sortable_key).await?;
+		}
+
+		Ok(())
+	}
+
+	async fn decrypt_sortable_key(&mut self, sortable_key: GroupSortableData) -> Result<(), SentcError>
+	{
+		let key_id = &sortable_key.encrypted_sortable_encryption_key_id;
+
+		if self.get_user_keys(key_id).is_none() {
+			self.fetch_user_key_internally(key_id, false).await?;
+		}
+
+		let k = self.get_user_keys(key_id).ok_or(SentcError::KeyNotFound)?;
+
+		let decrypted_sortable_key =
+			SdkGroup::<SGen, StGen, SignGen, SearchGen, SortGen, SC, StC, SignC, SearchC, SortC, PC, VC>::decrypt_group_sortable_key(
+				&k.group_key,
+				sortable_key,
+			)?;
+
+		self.sortable_keys.push(decrypted_sortable_key);
+
+		Ok(())
+	}
+
 	async fn decrypt_hmac_key(&mut self, hmac_key: GroupHmacData) -> Result<(), SentcError>
 	{
 		let key_id = &hmac_key.encrypted_hmac_encryption_key_id;
@@ -34,3 +34,10 @@ pub type TestGroup = sentc::keys::std::StdGroup;
 pub type TestGroup = sentc::keys::fips::FipsGroup;
 #[cfg(all(feature = "rec_keys", not(feature = "std_keys")))]
 pub type TestGroup = sentc::keys::rec::RecGroup;
+
+#[cfg(feature = "std_keys")]
+pub use sentc::keys::std::encrypt_string_for_user;
+#[cfg(all(feature = "fips_keys", not(feature = "std_keys")))]
+pub use sentc::keys::fips::encrypt_string_for_user;
+#[cfg(all(feature = "rec_keys", not(feature = "std_keys")))]
+pub use sentc::keys::rec::encrypt_string_for_user;
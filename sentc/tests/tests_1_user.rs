@@ -9,7 +9,7 @@ use sentc_crypto::SdkError;
 use tokio::sync::{OnceCell, RwLock};
 use tokio::time::sleep;
 
-use crate::test_mod::TestUser;
+use crate::test_mod::{encrypt_string_for_user, TestUser};
 
 mod test_mod;
 
@@ -684,6 +684,98 @@ async fn test_61_encrypt_data_for_other_user_with_sign()
 	assert_eq!(str, STRING_DATA);
 }
 
+#[tokio::test]
+async fn test_62_encrypt_data_without_user()
+{
+	let u1 = USER_2_TEST_STATE.get().unwrap().read().await;
+
+	let public_key_u1 = get_user_public_key_data(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		u1.0.get_user_id(),
+	)
+	.await
+	.unwrap();
+
+	let encrypted_string = encrypt_string_for_user(STRING_DATA, &public_key_u1).unwrap();
+
+	let head = split_head_and_encrypted_string(&encrypted_string).unwrap();
+
+	assert!(head.sign.is_none());
+
+	let str = u1.0.decrypt_string_sync(&encrypted_string, None).unwrap();
+
+	assert_eq!(str, STRING_DATA);
+}
+
+#[tokio::test]
+async fn test_63_sign_detached()
+{
+	let u = USER_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_2_TEST_STATE.get().unwrap().read().await;
+
+	let (head, sig) = u.0.sign_detached(STRING_DATA.as_bytes()).unwrap();
+
+	let vk_u = u1
+		.0
+		.get_user_verify_key_data(u.0.get_user_id(), &head.id)
+		.await
+		.unwrap();
+
+	let check = TestUser::verify_detached(STRING_DATA.as_bytes(), &sig, &vk_u).unwrap();
+
+	assert!(check);
+
+	//verify with other data
+	let check = TestUser::verify_detached(b"hello", &sig, &vk_u).unwrap();
+
+	assert!(!check);
+}
+
+#[tokio::test]
+async fn test_64_encrypt_for_multiple_users()
+{
+	let u = USER_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_2_TEST_STATE.get().unwrap().read().await;
+	let u2 = USER_3_TEST_STATE.get().unwrap().read().await;
+
+	let public_key_u1 =
+		u.0.get_user_public_key_data(u1.0.get_user_id())
+			.await
+			.unwrap();
+	let public_key_u2 =
+		u.0.get_user_public_key_data(u2.0.get_user_id())
+			.await
+			.unwrap();
+
+	let encrypted =
+		u.0.encrypt_for_users_sync(STRING_DATA.as_bytes(), &[public_key_u1, public_key_u2], true)
+			.unwrap();
+
+	//the user itself was not a recipient
+	match u.0.decrypt_for_users_sync(&encrypted, None) {
+		Err(SentcError::KeyNotFound) => {},
+		_ => panic!("should be error"),
+	}
+
+	let vk_u =
+		u1.0.get_user_verify_key_data(u.0.get_user_id(), &u.0.get_newest_key().unwrap().verify_key.key_id)
+			.await
+			.unwrap();
+
+	let decrypted =
+		u1.0.decrypt_for_users_sync(&encrypted, Some(&vk_u))
+			.unwrap();
+
+	assert_eq!(decrypted, STRING_DATA.as_bytes());
+
+	let decrypted =
+		u2.0.decrypt_for_users_sync(&encrypted, Some(&vk_u))
+			.unwrap();
+
+	assert_eq!(decrypted, STRING_DATA.as_bytes());
+}
+
 #[tokio::test]
 async fn zzz_clean_up()
 {
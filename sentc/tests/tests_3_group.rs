@@ -1,9 +1,13 @@
 use std::ops::{Deref, DerefMut};
 use std::time::Duration;
 
+use futures_util::TryStreamExt;
+use sentc::encrypt_options::EncryptOptions;
 use sentc::error::SentcError;
-use sentc::group::net::{GroupFetchResult, GroupFinishKeyRotation, GroupKeyFetchResult};
+use sentc::group::net::{GroupFetchResult, GroupFinishKeyRotation, GroupKeyFetchResult, GroupKeyVerifyMode, GroupKeyVerifyWarning};
+use sentc::group::rank::{GroupAction, GroupRank};
 use sentc::split_head_and_encrypted_string;
+use sentc_crypto::sdk_utils::cryptomat::SymKeyCrypto;
 use sentc_crypto::sdk_utils::error::SdkUtilError;
 use sentc_crypto::SdkError;
 use tokio::sync::{OnceCell, RwLock};
@@ -289,6 +293,10 @@ async fn test_11_get_all_groups_to_user()
 	let out = u.get_groups(Some(&out[0])).await.unwrap();
 
 	assert_eq!(out.len(), 0);
+
+	let out: Vec<_> = u.get_groups_stream().try_collect().await.unwrap();
+
+	assert_eq!(out.len(), 1);
 }
 
 #[tokio::test]
@@ -496,6 +504,94 @@ async fn test_21_encrypt_string_for_the_group()
 		.await;
 }
 
+#[tokio::test]
+async fn test_21_x_encrypt_with_options()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let options = EncryptOptions {
+		compress: false,
+		pad_to: Some(64),
+	};
+
+	let encrypted_a = g.encrypt_with_options_sync(b"a", &options).unwrap();
+	let encrypted_b = g.encrypt_with_options_sync(&[b'b'; 60], &options).unwrap();
+
+	//both are padded to the same length
+	assert_eq!(encrypted_a.len(), encrypted_b.len());
+
+	assert_eq!(g.decrypt_sync(&encrypted_a, None).unwrap(), b"a");
+	assert_eq!(g.decrypt_sync(&encrypted_b, None).unwrap(), [b'b'; 60]);
+
+	//data without options can still be decrypted
+	let encrypted = g.encrypt_sync(STRING_TO_ENCRYPT.as_bytes()).unwrap();
+
+	assert_eq!(
+		g.decrypt_sync(&encrypted, None).unwrap(),
+		STRING_TO_ENCRYPT.as_bytes()
+	);
+
+	#[cfg(feature = "compression")]
+	{
+		let data = STRING_TO_ENCRYPT.repeat(100);
+
+		let options = EncryptOptions {
+			compress: true,
+			pad_to: Some(64),
+		};
+
+		let encrypted = g
+			.encrypt_with_options_sync(data.as_bytes(), &options)
+			.unwrap();
+
+		assert!(encrypted.len() < data.len());
+
+		assert_eq!(g.decrypt_sync(&encrypted, None).unwrap(), data.as_bytes());
+	}
+}
+
+#[tokio::test]
+async fn test_21_y_encrypt_string_with_options()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let options = EncryptOptions {
+		compress: false,
+		pad_to: Some(64),
+	};
+
+	let encrypted_a = g.encrypt_string_with_options_sync("a", &options).unwrap();
+	let encrypted_b = g
+		.encrypt_string_with_options_sync(&"b".repeat(60), &options)
+		.unwrap();
+
+	assert_eq!(encrypted_a.len(), encrypted_b.len());
+
+	assert_eq!(g.decrypt_string_sync(&encrypted_a, None).unwrap(), "a");
+	assert_eq!(g.decrypt_string_sync(&encrypted_b, None).unwrap(), "b".repeat(60));
+
+	//strings without options can still be decrypted
+	let encrypted = g.encrypt_string_sync(STRING_TO_ENCRYPT).unwrap();
+
+	assert_eq!(g.decrypt_string_sync(&encrypted, None).unwrap(), STRING_TO_ENCRYPT);
+
+	#[cfg(feature = "compression")]
+	{
+		let data = STRING_TO_ENCRYPT.repeat(100);
+
+		let options = EncryptOptions {
+			compress: true,
+			pad_to: None,
+		};
+
+		let encrypted = g.encrypt_string_with_options_sync(&data, &options).unwrap();
+
+		assert!(encrypted.len() < data.len());
+
+		assert_eq!(g.decrypt_string_sync(&encrypted, None).unwrap(), data);
+	}
+}
+
 #[tokio::test]
 async fn test_22_start_key_rotation()
 {
@@ -690,6 +786,39 @@ async fn test_28_decrypt_with_sign_for_other_user()
 	assert_eq!(decrypted, STRING_TO_ENCRYPT);
 }
 
+#[tokio::test]
+async fn test_28_x_get_non_registered_key()
+{
+	let g0 = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let (key, server_out) = g0.generate_non_registered_key().unwrap();
+
+	let encrypted_string = key.encrypt_string(STRING_TO_ENCRYPT).unwrap();
+	let server_out = server_out.to_string().unwrap();
+
+	let mut g1 = GROUP_1_TEST_STATE.get().unwrap().write().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	let key = g1
+		.get_non_registered_key(u1.get_jwt().unwrap(), &server_out, Some(&u1.0), None)
+		.await
+		.unwrap();
+
+	let decrypted = key.decrypt_string(&encrypted_string, None).unwrap();
+
+	assert_eq!(decrypted, STRING_TO_ENCRYPT);
+
+	//the second call should use the cached key
+	let key = g1
+		.get_non_registered_key(u1.get_jwt().unwrap(), &server_out, Some(&u1.0), None)
+		.await
+		.unwrap();
+
+	let decrypted = key.decrypt_string(&encrypted_string, None).unwrap();
+
+	assert_eq!(decrypted, STRING_TO_ENCRYPT);
+}
+
 //__________________________________________________________________________________________________
 //join request
 
@@ -755,7 +884,7 @@ async fn test_31_not_reject_join_without_the_rights()
 		.await;
 
 	match err {
-		Err(SentcError::Sdk(SdkError::GroupPermission)) => {},
+		Err(SentcError::GroupPermission(GroupRank::Member, GroupAction::ManageJoinRequests)) => {},
 		_ => panic!("should be error"),
 	}
 }
@@ -805,7 +934,7 @@ async fn test_33_not_accept_without_rights()
 		.await;
 
 	match err {
-		Err(SentcError::Sdk(SdkError::GroupPermission)) => {},
+		Err(SentcError::GroupPermission(GroupRank::Member, GroupAction::ManageJoinRequests)) => {},
 		_ => panic!("should be error"),
 	}
 }
@@ -843,6 +972,27 @@ async fn test_35_fetch_group()
 		.await;
 }
 
+#[tokio::test]
+async fn test_35_x_get_all_member_with_stream()
+{
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let member = g.get_member(u0.get_jwt().unwrap(), None).await.unwrap();
+
+	let member_stream: Vec<_> = g
+		.get_member_stream(u0.get_jwt().unwrap())
+		.try_collect()
+		.await
+		.unwrap();
+
+	assert_eq!(member.len(), member_stream.len());
+
+	for (m1, m2) in member.iter().zip(member_stream.iter()) {
+		assert_eq!(m1.user_id, m2.user_id);
+	}
+}
+
 #[tokio::test]
 async fn test_36_decrypt_both_strings()
 {
@@ -874,7 +1024,30 @@ async fn test_37_not_kick_without_rights()
 	let err = g.kick_user(u0.get_jwt().unwrap(), u.get_user_id()).await;
 
 	match err {
-		Err(SentcError::Sdk(SdkError::GroupPermission)) => {},
+		Err(SentcError::GroupPermission(GroupRank::Member, GroupAction::Kick)) => {},
+		_ => panic!("should be error"),
+	}
+}
+
+#[tokio::test]
+async fn test_37_not_rotate_keys_without_rights()
+{
+	let g = GROUP_1_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	let rank = g.get_group_rank().unwrap();
+
+	assert_eq!(rank, GroupRank::Member);
+	assert!(!rank.can_invite());
+	assert!(!rank.can_kick(GroupRank::Member));
+	assert!(!rank.can_rotate_keys());
+
+	let err = g
+		.prepare_key_rotation(u0.get_jwt().unwrap(), false, Some(&u0.0), None)
+		.await;
+
+	match err {
+		Err(SentcError::GroupPermission(GroupRank::Member, GroupAction::RotateKeys)) => {},
 		_ => panic!("should be error"),
 	}
 }
@@ -888,11 +1061,11 @@ async fn test_38_increase_rank_for_user_1()
 
 	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
 
-	g.update_rank(u0.get_jwt().unwrap(), u.get_user_id(), 1)
+	g.update_rank(u0.get_jwt().unwrap(), u.get_user_id(), GroupRank::Admin)
 		.await
 		.unwrap();
 
-	g.update_rank(u0.get_jwt().unwrap(), u1.get_user_id(), 2)
+	g.update_rank(u0.get_jwt().unwrap(), u1.get_user_id(), GroupRank::Manager)
 		.await
 		.unwrap();
 
@@ -952,6 +1125,79 @@ async fn test_41_not_get_the_group_after_kick()
 	}
 }
 
+#[tokio::test]
+async fn test_42_invite_user_by_id()
+{
+	let u = USER_2_TEST_STATE.get().unwrap().read().await;
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+
+	//the public key of the user is signed
+	g.invite_user_by_id(u0.get_jwt().unwrap(), u.get_user_id(), None, true, true)
+		.await
+		.unwrap();
+
+	let (data, res) = u.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	let group = u.done_get_group(data, None, None).unwrap();
+
+	assert_eq!(group.get_group_id(), g.get_group_id());
+
+	g.kick_user(u0.get_jwt().unwrap(), u.get_user_id())
+		.await
+		.unwrap();
+}
+
+#[tokio::test]
+async fn test_43_accept_join_req_by_id()
+{
+	let u = USER_2_TEST_STATE.get().unwrap().read().await;
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+
+	u.group_join_request(g.get_group_id()).await.unwrap();
+
+	g.accept_join_request_by_id(u0.get_jwt().unwrap(), u.get_user_id(), None, true)
+		.await
+		.unwrap();
+
+	let (_data, res) = u.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	g.kick_user(u0.get_jwt().unwrap(), u.get_user_id())
+		.await
+		.unwrap();
+}
+
+#[tokio::test]
+async fn test_44_not_verify_a_public_key_without_signature()
+{
+	let u = USER_2_TEST_STATE.get().unwrap().read().await;
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+
+	let mut pk = u0.get_user_public_key_data(u.get_user_id()).await.unwrap();
+
+	g.check_user_public_key(u.get_user_id(), &pk)
+		.await
+		.unwrap();
+
+	pk.public_key_sig = None;
+
+	match g.check_user_public_key(u.get_user_id(), &pk).await {
+		Err(SentcError::UserPublicKeyNotVerified(id)) => assert_eq!(id, u.get_user_id()),
+		_ => panic!("should be error"),
+	}
+
+	//the key of another user is not signed by this user
+	let pk = u0.get_user_public_key_data(u0.get_user_id()).await.unwrap();
+
+	assert!(g.check_user_public_key(u.get_user_id(), &pk).await.is_err());
+}
+
 //__________________________________________________________________________________________________
 //child group
 
@@ -1012,6 +1258,29 @@ async fn test_51_get_child_group_as_member_of_the_parent_group()
 	);
 }
 
+#[tokio::test]
+async fn test_51_x_get_child_group_in_one_call()
+{
+	let cg = CHILD_GROUP.get().unwrap().read().await;
+
+	let g = GROUP_1_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	let child_group = g
+		.get_child_group(cg.get_group_id(), u1.get_jwt().unwrap())
+		.await
+		.unwrap();
+
+	assert_eq!(
+		child_group.get_newest_key().unwrap().group_key.key_id,
+		cg.get_newest_key().unwrap().group_key.key_id
+	);
+
+	let group = u1.get_group(g.get_group_id(), None).await.unwrap();
+
+	assert_eq!(group.get_rank(), g.get_rank());
+}
+
 #[tokio::test]
 async fn test_52_invite_a_user_to_the_child_group()
 {
@@ -1025,7 +1294,7 @@ async fn test_52_invite_a_user_to_the_child_group()
 	//test manually invite
 	let _invite = cg.prepare_group_keys_for_new_member(&pk, Some(2)).unwrap();
 
-	cg.invite_auto(u0.get_jwt().unwrap(), u.get_user_id(), &pk, Some(2))
+	cg.invite_auto(u0.get_jwt().unwrap(), u.get_user_id(), &pk, Some(GroupRank::Manager))
 		.await
 		.unwrap();
 
@@ -1441,6 +1710,234 @@ async fn test_63_finish_key_rotation_with_verify()
 	assert_ne!(old_newest_key, new_newest_key);
 }
 
+#[tokio::test]
+async fn test_64_fetch_group_and_verify_the_keys()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	let (data, res) = u1.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	let key_len = data.keys.len();
+
+	let (group, warnings) = u1
+		.done_get_group_verified(data, None, GroupKeyVerifyMode::Warn)
+		.await
+		.unwrap();
+
+	assert_eq!(
+		group.get_newest_key().unwrap().group_key.key_id,
+		g.get_newest_key().unwrap().group_key.key_id
+	);
+
+	//only the keys of the two rotations with sign are signed
+	assert_eq!(warnings.len(), key_len - 2);
+
+	for warning in warnings {
+		assert!(matches!(warning, GroupKeyVerifyWarning::NotSigned(_)));
+	}
+
+	//fail for the not signed keys
+	let (data, _) = u1.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+	let err = u1
+		.done_get_group_verified(data, None, GroupKeyVerifyMode::Fail)
+		.await;
+
+	match err {
+		Err(SentcError::GroupKeyNotSigned(_)) => {},
+		_ => panic!("should be error"),
+	}
+}
+
+#[tokio::test]
+async fn test_65_fetch_group_with_invalid_key_signature()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	let (mut data, _) = u1.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+	//swap the signatures of the two signed keys
+	let signed: Vec<usize> = data
+		.keys
+		.iter()
+		.enumerate()
+		.filter(|(_, k)| k.group_key_sig.is_some())
+		.map(|(i, _)| i)
+		.collect();
+
+	assert_eq!(signed.len(), 2);
+
+	let sig = data.keys[signed[0]].group_key_sig.take();
+	data.keys[signed[0]].group_key_sig = data.keys[signed[1]].group_key_sig.take();
+	data.keys[signed[1]].group_key_sig = sig;
+
+	let signed_ids: Vec<String> = signed
+		.iter()
+		.map(|i| data.keys[*i].group_key_id.clone())
+		.collect();
+
+	let (group, warnings) = u1
+		.done_get_group_verified(data, None, GroupKeyVerifyMode::Warn)
+		.await
+		.unwrap();
+
+	//the keys are loaded anyway
+	for id in &signed_ids {
+		assert!(group.get_group_key(id).is_some());
+	}
+
+	let invalid: Vec<&String> = warnings
+		.iter()
+		.filter_map(|w| {
+			match w {
+				GroupKeyVerifyWarning::InvalidSignature(id) => Some(id),
+				_ => None,
+			}
+		})
+		.collect();
+
+	assert_eq!(invalid.len(), 2);
+
+	for id in &signed_ids {
+		assert!(invalid.contains(&id));
+	}
+
+	//fail mode, only check the signed keys
+	let (mut data, _) = u1.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+	data.keys.retain(|k| k.group_key_sig.is_some());
+
+	let sig = data.keys[0].group_key_sig.take();
+	data.keys[0].group_key_sig = data.keys[1].group_key_sig.take();
+	data.keys[1].group_key_sig = sig;
+
+	match u1
+		.done_get_group_verified(data, None, GroupKeyVerifyMode::Fail)
+		.await
+	{
+		Err(SentcError::GroupKeyVerifyFailed(_)) => {},
+		_ => panic!("should be error"),
+	}
+
+	//the verify key of the signer can't be fetched
+	let (mut data, _) = u1.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+	for key in data.keys.iter_mut() {
+		if key.signed_by_user_sign_key_id.is_some() {
+			key.signed_by_user_sign_key_id = Some("not_a_key_id".to_string());
+		}
+	}
+
+	let (_, warnings) = u1
+		.done_get_group_verified(data, None, GroupKeyVerifyMode::Warn)
+		.await
+		.unwrap();
+
+	assert_eq!(
+		warnings
+			.iter()
+			.filter(|w| matches!(w, GroupKeyVerifyWarning::VerifyKeyNotFound(_)))
+			.count(),
+		2
+	);
+}
+
+#[tokio::test]
+async fn test_66_decrypt_for_multiple_users_with_group()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	let group_public_key = u0
+		.get_group_public_key_data(g.get_group_id())
+		.await
+		.unwrap();
+	let public_key_u1 = u0.get_user_public_key_data(u1.get_user_id()).await.unwrap();
+
+	let encrypted = u0
+		.encrypt_for_users_sync(STRING_TO_ENCRYPT.as_bytes(), &[group_public_key, public_key_u1.clone()], true)
+		.unwrap();
+
+	let vk = u0
+		.get_user_verify_key_data(u0.get_user_id(), &u0.get_newest_key().unwrap().verify_key.key_id)
+		.await
+		.unwrap();
+
+	let decrypted = g.decrypt_for_users_sync(&encrypted, Some(&vk)).unwrap();
+
+	assert_eq!(decrypted, STRING_TO_ENCRYPT.as_bytes());
+
+	let decrypted = u1.decrypt_for_users_sync(&encrypted, Some(&vk)).unwrap();
+
+	assert_eq!(decrypted, STRING_TO_ENCRYPT.as_bytes());
+
+	//the group was not a recipient
+	let encrypted = u0
+		.encrypt_for_users_sync(STRING_TO_ENCRYPT.as_bytes(), &[public_key_u1], false)
+		.unwrap();
+
+	match g.decrypt_for_users_sync(&encrypted, None) {
+		Err(SentcError::KeyNotFound) => {},
+		_ => panic!("should be error"),
+	}
+}
+
+#[tokio::test]
+async fn test_67_transfer_ownership()
+{
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u = USER_3_TEST_STATE.get().unwrap().read().await;
+
+	let group_id = u0.create_group(false).await.unwrap();
+
+	let mut g = u0.get_group(&group_id, None).await.unwrap();
+
+	g.invite_user_by_id(u0.get_jwt().unwrap(), u.get_user_id(), None, true, false)
+		.await
+		.unwrap();
+
+	let mut g3 = u.get_group(&group_id, None).await.unwrap();
+
+	//only the creator can hand over the group
+	match g3
+		.transfer_ownership(u.get_jwt().unwrap(), u.get_user_id(), u0.get_user_id())
+		.await
+	{
+		Err(SentcError::GroupPermission(GroupRank::Member, GroupAction::TransferOwnership)) => {},
+		_ => panic!("should be error"),
+	}
+
+	g.transfer_ownership(u0.get_jwt().unwrap(), u0.get_user_id(), u.get_user_id())
+		.await
+		.unwrap();
+
+	assert_eq!(g.get_group_rank().unwrap(), GroupRank::Admin);
+
+	let member = g.get_member(u0.get_jwt().unwrap(), None).await.unwrap();
+
+	for m in &member {
+		if m.user_id == u.get_user_id() {
+			assert_eq!(m.rank, GroupRank::Creator.as_i32());
+		} else if m.user_id == u0.get_user_id() {
+			assert_eq!(m.rank, GroupRank::Admin.as_i32());
+		}
+	}
+
+	g3.group_update_check(u.get_jwt().unwrap()).await.unwrap();
+
+	assert_eq!(g3.get_group_rank().unwrap(), GroupRank::Creator);
+
+	//the old creator is not the creator anymore and can leave the group
+	g.leave(u0.get_jwt().unwrap()).await.unwrap();
+
+	g3.delete_group(u.get_jwt().unwrap()).await.unwrap();
+}
+
 #[tokio::test]
 async fn zzz_clean_up()
 {
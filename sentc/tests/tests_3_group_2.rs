@@ -2,6 +2,7 @@ use std::ops::{Deref, DerefMut};
 
 use sentc::error::SentcError;
 use sentc::group::net::{GroupFetchResult, GroupKeyFetchResult};
+use sentc::group::rank::GroupRank;
 use sentc_crypto::SdkError;
 use tokio::sync::{OnceCell, RwLock};
 
@@ -200,6 +201,26 @@ async fn test_11_create_connected_group()
 		.await;
 }
 
+#[tokio::test]
+async fn test_11_x_get_connected_group_in_one_call()
+{
+	let con_group = CONNECTED_GROUP.get().unwrap().read().await;
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+
+	let group = g
+		.get_connected_group(con_group.get_group_id(), u0.get_jwt().unwrap())
+		.await
+		.unwrap();
+
+	assert_eq!(group.get_group_id(), con_group.get_group_id());
+	assert_eq!(group.access_by_group_as_member(), Some(&g.get_group_id().to_string()));
+	assert_eq!(
+		group.get_newest_key().unwrap().group_key.key_id,
+		con_group.get_newest_key().unwrap().group_key.key_id
+	);
+}
+
 #[tokio::test]
 async fn test_12_key_rotation_in_connected_group()
 {
@@ -532,6 +553,78 @@ async fn test_26_get_all_connected_groups()
 	assert_eq!(list_2.len(), 0);
 }
 
+#[tokio::test]
+async fn test_27_invite_group_by_id()
+{
+	let g = CONNECTED_GROUP.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+
+	let g1 = GROUP_1_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	g.kick_user(u0.get_jwt().unwrap(), g1.get_group_id())
+		.await
+		.unwrap();
+
+	//the public key of the group is fetched
+	g.invite_group_by_id(u0.get_jwt().unwrap(), g1.get_group_id(), None, true)
+		.await
+		.unwrap();
+
+	let (data, res) = g1
+		.prepare_get_connected_group(g.get_group_id(), u1.get_jwt().unwrap())
+		.await
+		.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	let gc = g1.done_get_connected_group(data, None).unwrap();
+
+	assert_eq!(gc.get_access_group_as_member().unwrap(), g1.get_group_id());
+}
+
+#[tokio::test]
+async fn test_28_accept_group_join_req_by_id()
+{
+	let g = CONNECTED_GROUP.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+
+	let g1 = GROUP_1_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	g.kick_user(u0.get_jwt().unwrap(), g1.get_group_id())
+		.await
+		.unwrap();
+
+	g1.group_join_request(u1.get_jwt().unwrap(), g.get_group_id())
+		.await
+		.unwrap();
+
+	let joins = g
+		.get_join_requests(u0.get_jwt().unwrap(), None)
+		.await
+		.unwrap();
+
+	assert_eq!(joins[0].user_type, 2);
+
+	//the group public key is used for the join request of a group
+	g.accept_group_join_request_by_id(u0.get_jwt().unwrap(), &joins[0].user_id, Some(GroupRank::Manager))
+		.await
+		.unwrap();
+
+	let (data, res) = g1
+		.prepare_get_connected_group(g.get_group_id(), u1.get_jwt().unwrap())
+		.await
+		.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	let gc = g1.done_get_connected_group(data, None).unwrap();
+
+	assert_eq!(gc.get_access_group_as_member().unwrap(), g1.get_group_id());
+	assert_eq!(gc.get_group_rank().unwrap(), GroupRank::Manager);
+}
+
 #[tokio::test]
 async fn zzz_clean_up()
 {
@@ -0,0 +1,539 @@
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+use sentc::error::SentcError;
+use sentc::group::net::{GroupFetchResult, GroupKeyFetchResult};
+use sentc::group::rank::GroupRank;
+use tokio::sync::{OnceCell, RwLock};
+use tokio::time::sleep;
+
+use crate::test_mod::{TestGroup, TestUser};
+
+mod test_mod;
+
+struct UserState(TestUser);
+
+impl Deref for UserState
+{
+	type Target = TestUser;
+
+	fn deref(&self) -> &Self::Target
+	{
+		&self.0
+	}
+}
+
+impl DerefMut for UserState
+{
+	fn deref_mut(&mut self) -> &mut Self::Target
+	{
+		&mut self.0
+	}
+}
+
+struct GroupState(TestGroup);
+
+impl Deref for GroupState
+{
+	type Target = TestGroup;
+
+	fn deref(&self) -> &Self::Target
+	{
+		&self.0
+	}
+}
+
+impl DerefMut for GroupState
+{
+	fn deref_mut(&mut self) -> &mut Self::Target
+	{
+		&mut self.0
+	}
+}
+
+struct EncryptedString(String);
+
+static USER_0_TEST_STATE: OnceCell<RwLock<UserState>> = OnceCell::const_new();
+static USER_1_TEST_STATE: OnceCell<RwLock<UserState>> = OnceCell::const_new();
+static USER_2_TEST_STATE: OnceCell<RwLock<UserState>> = OnceCell::const_new();
+
+static GROUP_0_TEST_STATE: OnceCell<RwLock<GroupState>> = OnceCell::const_new();
+
+static ENCRYPTED_STRING: OnceCell<RwLock<EncryptedString>> = OnceCell::const_new();
+
+const USERNAME0: &str = "test0";
+const USERNAME1: &str = "test1";
+const USERNAME2: &str = "test2";
+
+const PW: &str = "12345";
+
+const STRING_TO_ENCRYPT: &str = "hello there £ Я a a 👍";
+
+//the group got 200 keys after the rotations, a multiple of the page size
+const ROTATIONS: usize = 199;
+
+#[tokio::test]
+async fn aaa_init_global_test()
+{
+	TestUser::register(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME0,
+		PW,
+	)
+	.await
+	.unwrap();
+	let user = TestUser::login_forced(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME0,
+		PW,
+	)
+	.await
+	.unwrap();
+	USER_0_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(UserState(user)) })
+		.await;
+
+	TestUser::register(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME1,
+		PW,
+	)
+	.await
+	.unwrap();
+	let user = TestUser::login_forced(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME1,
+		PW,
+	)
+	.await
+	.unwrap();
+	USER_1_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(UserState(user)) })
+		.await;
+
+	TestUser::register(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME2,
+		PW,
+	)
+	.await
+	.unwrap();
+	let user = TestUser::login_forced(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME2,
+		PW,
+	)
+	.await
+	.unwrap();
+	USER_2_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(UserState(user)) })
+		.await;
+}
+
+#[tokio::test]
+async fn test_10_create_group_and_encrypt()
+{
+	let u = USER_0_TEST_STATE.get().unwrap().read().await;
+
+	let group_id = u.create_group(false).await.unwrap();
+
+	let (data, res) = u.prepare_get_group(&group_id, None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	let group = u.done_get_group(data, None, None).unwrap();
+
+	//encrypt with the first key
+	let encrypted_string = group.encrypt_string_sync(STRING_TO_ENCRYPT).unwrap();
+
+	ENCRYPTED_STRING
+		.get_or_init(|| async { RwLock::new(EncryptedString(encrypted_string)) })
+		.await;
+
+	GROUP_0_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(GroupState(group)) })
+		.await;
+}
+
+#[tokio::test]
+async fn test_11_rotate_keys()
+{
+	let mut g = GROUP_0_TEST_STATE.get().unwrap().write().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+
+	for _ in 0..ROTATIONS {
+		let res = g
+			.prepare_key_rotation(u0.get_jwt().unwrap(), false, Some(&u0.0), None)
+			.await
+			.unwrap();
+
+		let data = match res {
+			GroupKeyFetchResult::Ok(data) => data,
+			_ => {
+				panic!("should be no missing key or done");
+			},
+		};
+
+		g.done_fetch_group_key_after_rotation(data, Some(&u0.0), None, None)
+			.unwrap();
+	}
+
+	sleep(Duration::from_millis(300)).await;
+}
+
+#[tokio::test]
+async fn test_12_fetch_all_keys()
+{
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let (data, res) = u0.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+	assert_eq!(data.keys.len(), ROTATIONS + 1);
+
+	let group = u0.done_get_group(data, None, None).unwrap();
+
+	assert!(group.has_all_keys());
+	assert_eq!(
+		group.get_newest_key().unwrap().group_key.key_id,
+		g.get_newest_key().unwrap().group_key.key_id
+	);
+
+	let string = ENCRYPTED_STRING.get().unwrap().read().await;
+
+	let decrypted = group.decrypt_string_sync(&string.0, None).unwrap();
+
+	assert_eq!(decrypted, STRING_TO_ENCRYPT);
+}
+
+#[tokio::test]
+async fn test_13_fetch_newest_keys_only()
+{
+	let mut u0 = USER_0_TEST_STATE.get().unwrap().write().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	u0.set_group_key_limit(Some(10));
+
+	let (data, res) = u0.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	//the first key is needed for the hmac and sortable keys
+	assert_eq!(data.keys.len(), 11);
+
+	let mut group = u0.done_get_group(data, None, None).unwrap();
+
+	u0.set_group_key_limit(None);
+
+	assert!(!group.has_all_keys());
+	assert_eq!(
+		group.get_newest_key().unwrap().group_key.key_id,
+		g.get_newest_key().unwrap().group_key.key_id
+	);
+
+	//new member needs all keys
+	let pk = u0.get_user_public_key_data(u1.get_user_id()).await.unwrap();
+
+	match group.prepare_group_keys_for_new_member(&pk, None) {
+		Err(SentcError::GroupOlderKeysNotLoaded) => {},
+		_ => panic!("should be error"),
+	}
+
+	let string = ENCRYPTED_STRING.get().unwrap().read().await;
+
+	let data = g.encrypt_string_sync(STRING_TO_ENCRYPT).unwrap();
+
+	//encrypted with the newest key
+	let decrypted = group.decrypt_string_sync(&data, None).unwrap();
+
+	assert_eq!(decrypted, STRING_TO_ENCRYPT);
+
+	//encrypted with an older key which is not loaded
+	let key_id = match group.decrypt_string_sync(&string.0, None) {
+		Err(SentcError::KeyRequired(id)) => id,
+		_ => panic!("should be error"),
+	};
+
+	let res = group
+		.load_group_key(&key_id, u0.get_jwt().unwrap(), Some(&u0.0), None, None)
+		.await
+		.unwrap();
+
+	assert!(matches!(res, GroupKeyFetchResult::Done));
+
+	let decrypted = group.decrypt_string_sync(&string.0, None).unwrap();
+
+	assert_eq!(decrypted, STRING_TO_ENCRYPT);
+
+	//load the rest
+	let res = group
+		.load_older_keys(u0.get_jwt().unwrap(), Some(&u0.0), None)
+		.await
+		.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+	assert!(group.has_all_keys());
+
+	group.prepare_group_keys_for_new_member(&pk, None).unwrap();
+}
+
+#[tokio::test]
+async fn test_14_invite_user_with_all_keys()
+{
+	let mut g = GROUP_0_TEST_STATE.get().unwrap().write().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	//one more key, so the last key page got only one key
+	let res = g
+		.prepare_key_rotation(u0.get_jwt().unwrap(), false, Some(&u0.0), None)
+		.await
+		.unwrap();
+
+	let data = match res {
+		GroupKeyFetchResult::Ok(data) => data,
+		_ => {
+			panic!("should be no missing key or done");
+		},
+	};
+
+	g.done_fetch_group_key_after_rotation(data, Some(&u0.0), None, None)
+		.unwrap();
+
+	sleep(Duration::from_millis(300)).await;
+
+	let pk = u0.get_user_public_key_data(u1.get_user_id()).await.unwrap();
+
+	g.invite_auto(u0.get_jwt().unwrap(), u1.get_user_id(), &pk, None)
+		.await
+		.unwrap();
+
+	let (data, res) = u1.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+	assert_eq!(data.keys.len(), ROTATIONS + 2);
+
+	let group = u1.done_get_group(data, None, None).unwrap();
+
+	let string = ENCRYPTED_STRING.get().unwrap().read().await;
+
+	let decrypted = group.decrypt_string_sync(&string.0, None).unwrap();
+
+	assert_eq!(decrypted, STRING_TO_ENCRYPT);
+}
+
+#[tokio::test]
+async fn test_15_kick_many()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	let res = g
+		.kick_many(u0.get_jwt().unwrap(), &[u1.get_user_id()], 5)
+		.await
+		.unwrap();
+
+	assert_eq!(res.len(), 1);
+	assert_eq!(res[0].user_id, u1.get_user_id());
+	assert!(res[0].result.is_ok());
+
+	let member = g.get_member(u0.get_jwt().unwrap(), None).await.unwrap();
+
+	assert!(!member.iter().any(|m| m.user_id == u1.get_user_id()));
+}
+
+#[tokio::test]
+async fn test_16_invite_many_with_key_session()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+	let u2 = USER_2_TEST_STATE.get().unwrap().read().await;
+
+	let res = g
+		.invite_many(
+			u0.get_jwt().unwrap(),
+			&[u1.get_user_id(), u2.get_user_id()],
+			None,
+			true,
+			2,
+		)
+		.await
+		.unwrap();
+
+	assert_eq!(res.len(), 2);
+	assert_eq!(res[0].user_id, u1.get_user_id());
+	assert_eq!(res[1].user_id, u2.get_user_id());
+
+	for r in &res {
+		assert!(r.result.is_ok());
+	}
+
+	//all keys are sent in the key session
+	let string = ENCRYPTED_STRING.get().unwrap().read().await;
+
+	for u in [&u1, &u2] {
+		let (data, res) = u.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+		assert!(matches!(res, GroupFetchResult::Ok));
+		assert_eq!(data.keys.len(), ROTATIONS + 2);
+
+		let group = u.done_get_group(data, None, None).unwrap();
+
+		let decrypted = group.decrypt_string_sync(&string.0, None).unwrap();
+
+		assert_eq!(decrypted, STRING_TO_ENCRYPT);
+	}
+}
+
+#[tokio::test]
+async fn test_17_update_rank_many()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+	let u2 = USER_2_TEST_STATE.get().unwrap().read().await;
+
+	let res = g
+		.update_rank_many(
+			u0.get_jwt().unwrap(),
+			&[u1.get_user_id(), u2.get_user_id()],
+			GroupRank::Manager,
+			2,
+		)
+		.await
+		.unwrap();
+
+	assert_eq!(res.len(), 2);
+
+	for r in &res {
+		assert!(r.result.is_ok());
+	}
+
+	let member = g.get_member(u0.get_jwt().unwrap(), None).await.unwrap();
+
+	for u in [&u1, &u2] {
+		let m = member
+			.iter()
+			.find(|m| m.user_id == u.get_user_id())
+			.unwrap();
+
+		assert_eq!(m.rank, 2);
+	}
+}
+
+#[tokio::test]
+async fn test_17_x_update_rank_many_with_an_invalid_user()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	//only the invalid user fails, the call itself succeeds
+	let res = g
+		.update_rank_many(
+			u0.get_jwt().unwrap(),
+			&[u1.get_user_id(), "not_a_user_id"],
+			GroupRank::ContentManager,
+			2,
+		)
+		.await
+		.unwrap();
+
+	assert_eq!(res.len(), 2);
+	assert_eq!(res[0].user_id, u1.get_user_id());
+	assert!(res[0].result.is_ok());
+	assert_eq!(res[1].user_id, "not_a_user_id");
+	assert!(res[1].result.is_err());
+
+	let member = g.get_member(u0.get_jwt().unwrap(), None).await.unwrap();
+
+	let m = member
+		.iter()
+		.find(|m| m.user_id == u1.get_user_id())
+		.unwrap();
+
+	assert_eq!(m.rank, 3);
+}
+
+#[tokio::test]
+async fn test_18_kick_and_rotate()
+{
+	let mut g = GROUP_0_TEST_STATE.get().unwrap().write().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+	let u2 = USER_2_TEST_STATE.get().unwrap().read().await;
+
+	let old_key_id = g.get_newest_key().unwrap().group_key.key_id.clone();
+
+	g.kick_user_and_rotate(u0.get_jwt().unwrap(), u2.get_user_id(), false, Some(&u0.0), None)
+		.await
+		.unwrap();
+
+	assert_ne!(g.get_newest_key().unwrap().group_key.key_id, old_key_id);
+
+	let res = g
+		.kick_many_and_rotate(
+			u0.get_jwt().unwrap(),
+			&[u1.get_user_id(), "not_a_user_id"],
+			2,
+			false,
+			Some(&u0.0),
+			None,
+		)
+		.await
+		.unwrap();
+
+	assert_eq!(res.kicked.len(), 2);
+	assert!(res.kicked[0].result.is_ok());
+	assert!(res.kicked[1].result.is_err());
+	assert!(matches!(res.rotation, Some(Ok(()))));
+
+	sleep(Duration::from_millis(300)).await;
+
+	let (data, res) = u0.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+	assert_eq!(data.keys.len(), ROTATIONS + 4);
+
+	for u in [&u1, &u2] {
+		assert!(u.prepare_get_group(g.get_group_id(), None).await.is_err());
+	}
+
+	//no member was kicked, so the keys are not rotated
+	let res = g
+		.kick_many_and_rotate(u0.get_jwt().unwrap(), &["not_a_user_id"], 2, false, Some(&u0.0), None)
+		.await
+		.unwrap();
+
+	assert!(res.kicked[0].result.is_err());
+	assert!(res.rotation.is_none());
+}
+
+#[tokio::test]
+async fn zzz_clean_up()
+{
+	let u = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	g.delete_group(u.get_jwt().unwrap()).await.unwrap();
+
+	u.delete(PW, None, None).await.unwrap();
+
+	u1.delete(PW, None, None).await.unwrap();
+
+	let u2 = USER_2_TEST_STATE.get().unwrap().read().await;
+
+	u2.delete(PW, None, None).await.unwrap();
+}
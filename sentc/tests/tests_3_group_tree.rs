@@ -0,0 +1,358 @@
+use std::ops::Deref;
+use std::time::Duration;
+
+use sentc::group::net::GroupFetchResult;
+use sentc::group::rank::GroupRank;
+use sentc::group::tree::{GroupTree, GroupTreeOptions};
+use tokio::sync::{OnceCell, RwLock};
+use tokio::time::sleep;
+
+use crate::test_mod::{TestGroup, TestUser};
+
+mod test_mod;
+
+struct UserState(TestUser);
+
+impl Deref for UserState
+{
+	type Target = TestUser;
+
+	fn deref(&self) -> &Self::Target
+	{
+		&self.0
+	}
+}
+
+struct TreeIds
+{
+	root: String,
+	child_1: String,
+	child_2: String,
+	grand_child: String,
+}
+
+static USER_0_TEST_STATE: OnceCell<RwLock<UserState>> = OnceCell::const_new();
+static USER_1_TEST_STATE: OnceCell<RwLock<UserState>> = OnceCell::const_new();
+
+static TREE_IDS: OnceCell<RwLock<TreeIds>> = OnceCell::const_new();
+
+const USERNAME0: &str = "test0";
+const USERNAME1: &str = "test1";
+
+const PW: &str = "12345";
+
+async fn load_root(u: &TestUser, group_id: &str) -> TestGroup
+{
+	let (data, res) = u.prepare_get_group(group_id, None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	u.done_get_group(data, None, None).unwrap()
+}
+
+#[tokio::test]
+async fn aaa_init_global_test()
+{
+	TestUser::register(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME0,
+		PW,
+	)
+	.await
+	.unwrap();
+	let user = TestUser::login_forced(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME0,
+		PW,
+	)
+	.await
+	.unwrap();
+
+	//root -> child 1 -> grand child
+	//     -> child 2
+	let root_id = user.create_group(false).await.unwrap();
+	let root = load_root(&user, &root_id).await;
+
+	let child_1 = root
+		.create_child_group(user.get_jwt().unwrap())
+		.await
+		.unwrap();
+	let child_2 = root
+		.create_child_group(user.get_jwt().unwrap())
+		.await
+		.unwrap();
+
+	let (data, res) = root
+		.prepare_get_child_group(&child_1, user.get_jwt().unwrap())
+		.await
+		.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	let child_1_group = root.done_get_child_group(data, None).unwrap();
+
+	let grand_child = child_1_group
+		.create_child_group(user.get_jwt().unwrap())
+		.await
+		.unwrap();
+
+	USER_0_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(UserState(user)) })
+		.await;
+
+	TestUser::register(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME1,
+		PW,
+	)
+	.await
+	.unwrap();
+	let user = TestUser::login_forced(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME1,
+		PW,
+	)
+	.await
+	.unwrap();
+
+	USER_1_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(UserState(user)) })
+		.await;
+
+	TREE_IDS
+		.get_or_init(|| {
+			async move {
+				RwLock::new(TreeIds {
+					root: root_id,
+					child_1,
+					child_2,
+					grand_child,
+				})
+			}
+		})
+		.await;
+}
+
+#[tokio::test]
+async fn test_10_load_tree()
+{
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let ids = TREE_IDS.get().unwrap().read().await;
+
+	let root = load_root(&u0, &ids.root).await;
+
+	let tree = GroupTree::load(root, u0.get_jwt().unwrap(), GroupTreeOptions::default())
+		.await
+		.unwrap();
+
+	assert_eq!(tree.len(), 4);
+	assert_eq!(tree.get_root().get_group_id(), ids.root);
+
+	let walked: Vec<&str> = tree
+		.walk()
+		.map(|node| node.get_group().get_group_id())
+		.collect();
+
+	assert_eq!(
+		walked,
+		vec![ids.root.as_str(), ids.child_1.as_str(), ids.grand_child.as_str(), ids.child_2.as_str()]
+	);
+
+	assert_eq!(
+		tree.get_path(&ids.grand_child).unwrap(),
+		vec![ids.root.as_str(), ids.child_1.as_str(), ids.grand_child.as_str()]
+	);
+
+	let first_level = tree.search(|node| node.get_depth() == 1);
+
+	assert_eq!(first_level.len(), 2);
+
+	for node in first_level {
+		assert_eq!(node.get_parent_id(), Some(ids.root.as_str()));
+	}
+
+	let (group, parent) = tree.get_with_parent(&ids.grand_child).unwrap();
+
+	assert_eq!(group.get_group_id(), ids.grand_child);
+	assert_eq!(parent.unwrap().get_group_id(), ids.child_1);
+
+	//the keys of the children are loaded
+	let encrypted = group.encrypt_string_sync("hello").unwrap();
+	let decrypted = tree
+		.get(&ids.grand_child)
+		.unwrap()
+		.decrypt_string_sync(&encrypted, None)
+		.unwrap();
+
+	assert_eq!(decrypted, "hello");
+}
+
+#[tokio::test]
+async fn test_11_load_tree_with_max_depth()
+{
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let ids = TREE_IDS.get().unwrap().read().await;
+
+	let root = load_root(&u0, &ids.root).await;
+
+	let tree = GroupTree::load(
+		root,
+		u0.get_jwt().unwrap(),
+		GroupTreeOptions {
+			max_depth: 1,
+			concurrency: 1,
+		},
+	)
+	.await
+	.unwrap();
+
+	assert_eq!(tree.len(), 3);
+	assert!(tree.get(&ids.grand_child).is_none());
+	assert!(tree
+		.get_node(&ids.child_1)
+		.unwrap()
+		.get_children()
+		.is_empty());
+}
+
+#[tokio::test]
+async fn test_12_refresh_tree()
+{
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let mut ids = TREE_IDS.get().unwrap().write().await;
+
+	let root = load_root(&u0, &ids.root).await;
+
+	let mut tree = GroupTree::load(root, u0.get_jwt().unwrap(), GroupTreeOptions::default())
+		.await
+		.unwrap();
+
+	//delete child 1 with the grand child and create a new child under child 2
+	tree.get(&ids.child_1)
+		.unwrap()
+		.delete_group(u0.get_jwt().unwrap())
+		.await
+		.unwrap();
+
+	let new_child = tree
+		.get(&ids.child_2)
+		.unwrap()
+		.create_child_group(u0.get_jwt().unwrap())
+		.await
+		.unwrap();
+
+	//the deleted groups failed, but the other groups are still checked
+	let errors = tree.refresh(u0.get_jwt().unwrap()).await.unwrap();
+
+	let mut failed: Vec<&str> = errors.iter().map(|e| e.group_id.as_str()).collect();
+	failed.sort();
+
+	let mut deleted = vec![ids.child_1.as_str(), ids.grand_child.as_str()];
+	deleted.sort();
+
+	assert_eq!(failed, deleted);
+
+	//refresh doesn't fetch the children
+	assert!(tree.get(&new_child).is_none());
+
+	tree.reload_children(u0.get_jwt().unwrap(), &ids.root)
+		.await
+		.unwrap();
+
+	assert_eq!(tree.len(), 3);
+	assert!(tree.get(&ids.child_1).is_none());
+	assert!(tree.get(&ids.grand_child).is_none());
+
+	let node = tree.get_node(&new_child).unwrap();
+
+	assert_eq!(node.get_depth(), 2);
+	assert_eq!(node.get_parent_id(), Some(ids.child_2.as_str()));
+	assert_eq!(tree.get_root().get_group_id(), ids.root);
+
+	ids.grand_child = new_child;
+}
+
+#[tokio::test]
+async fn test_13_finish_child_key_rotation_with_refresh()
+{
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+	let ids = TREE_IDS.get().unwrap().read().await;
+
+	let root = load_root(&u0, &ids.root).await;
+
+	let mut tree = GroupTree::load(root, u0.get_jwt().unwrap(), GroupTreeOptions::default())
+		.await
+		.unwrap();
+
+	//a direct member of child 2 starts the rotation, so the root group must finish it
+	tree.get(&ids.child_2)
+		.unwrap()
+		.invite_auto(
+			u0.get_jwt().unwrap(),
+			u1.get_user_id(),
+			u1.get_newest_exported_public_key().unwrap(),
+			Some(GroupRank::Admin),
+		)
+		.await
+		.unwrap();
+
+	let mut child = load_root(&u1, &ids.child_2).await;
+
+	child
+		.key_rotation(u1.get_jwt().unwrap(), false, Some(&u1.0), None)
+		.await
+		.unwrap();
+
+	//wait until the server is done with the rotation
+	sleep(Duration::from_millis(300)).await;
+
+	let old_key = tree
+		.get(&ids.child_2)
+		.unwrap()
+		.get_newest_key()
+		.unwrap()
+		.group_key
+		.key_id
+		.clone();
+
+	let new_key = &child.get_newest_key().unwrap().group_key.key_id;
+
+	assert_ne!(&old_key, new_key);
+
+	let errors = tree.refresh(u0.get_jwt().unwrap()).await.unwrap();
+
+	assert!(errors.is_empty());
+
+	assert_eq!(
+		&tree
+			.get(&ids.child_2)
+			.unwrap()
+			.get_newest_key()
+			.unwrap()
+			.group_key
+			.key_id,
+		new_key
+	);
+}
+
+#[tokio::test]
+async fn zzz_clean_up()
+{
+	let u = USER_0_TEST_STATE.get().unwrap().read().await;
+	let ids = TREE_IDS.get().unwrap().read().await;
+
+	let root = load_root(&u, &ids.root).await;
+
+	root.delete_group(u.get_jwt().unwrap()).await.unwrap();
+
+	u.delete(PW, None, None).await.unwrap();
+
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	u1.delete(PW, None, None).await.unwrap();
+}
@@ -1,6 +1,7 @@
 use std::ops::Deref;
 
 use sentc::group::net::GroupFetchResult;
+use sentc::searchable::SearchMode;
 use tokio::sync::{OnceCell, RwLock};
 
 use crate::test_mod::{TestGroup, TestUser};
@@ -178,6 +179,111 @@ async fn test_14_search_item_in_parts()
 	assert!(str.contains(&str_item));
 }
 
+#[tokio::test]
+async fn test_15_search_item_with_all_keys()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let g1 = GROUP_1_TEST_STATE.get().unwrap().read().await;
+
+	let out = g.create_search(STR, true, None).unwrap();
+
+	let items = g1.search_all(STR).unwrap();
+
+	assert!(!items.is_empty());
+
+	let (_, hash) = items
+		.iter()
+		.find(|(key_id, _)| *key_id == out.key_id)
+		.unwrap();
+
+	assert_eq!(*hash, out.hashes[0]);
+}
+
+#[tokio::test]
+async fn test_16_search_index_with_fields()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let g1 = GROUP_1_TEST_STATE.get().unwrap().read().await;
+
+	let index = g
+		.search_index()
+		.field("email", "hello@sentc.com", SearchMode::Full)
+		.field("name", "hello@sentc.com", SearchMode::Prefix(None))
+		.tokenized_field("title", "Hello World  Hello", SearchMode::Full)
+		.build()
+		.unwrap();
+
+	assert_eq!(index.fields.len(), 3);
+	assert_eq!(index.fields[0].hashes.len(), 1);
+	assert_eq!(index.fields[1].hashes.len(), "hello@sentc.com".len());
+	//hello is only indexed once
+	assert_eq!(index.fields[2].hashes.len(), 2);
+
+	let email_hash = &index.fields[0].hashes[0];
+
+	//same value but different fields
+	assert!(!index.fields[1].hashes.contains(email_hash));
+
+	let query = g1
+		.search_query()
+		.field("email", "hello@sentc.com")
+		.field("name", "hello")
+		.tokenized_field("title", "WORLD")
+		.build()
+		.unwrap();
+
+	assert_eq!(query.key_id, index.key_id);
+	assert_eq!(query.fields[0].hashes[0], *email_hash);
+	assert!(index.fields[1].hashes.contains(&query.fields[1].hashes[0]));
+	assert!(index.fields[2].hashes.contains(&query.fields[2].hashes[0]));
+
+	//the email field is not searchable by prefix
+	let query = g1.search_query().field("email", "hello").build().unwrap();
+
+	assert!(!index.fields[0].hashes.contains(&query.fields[0].hashes[0]));
+}
+
+#[tokio::test]
+async fn test_17_search_fields_with_null_bytes()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	//the same bytes when field and value are only joined with a null byte
+	let query = g
+		.search_query()
+		.field("a", "\0b")
+		.field("a\0", "b")
+		.build()
+		.unwrap();
+
+	assert_ne!(query.fields[0].hashes[0], query.fields[1].hashes[0]);
+}
+
+#[tokio::test]
+async fn test_20_create_search_for_user()
+{
+	let u = USER_0_TEST_STATE.get().unwrap().read().await;
+
+	let str = u.create_search_raw(STR, false, None).unwrap();
+
+	assert_eq!(str.len(), 39);
+
+	let str_item = u.search(STR).unwrap();
+
+	assert!(str.contains(&str_item));
+
+	let str_item = u.search("123").unwrap();
+
+	assert!(str.contains(&str_item));
+
+	//the user got other keys than the group
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let group_item = g.search(STR).unwrap();
+
+	assert!(!str.contains(&group_item));
+}
+
 #[tokio::test]
 async fn zzz_clean_up()
 {
@@ -1,9 +1,15 @@
 #[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
-use std::ops::Deref;
+use std::ops::{Bound, Deref, RangeBounds};
 
 #[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
 use sentc::group::net::GroupFetchResult;
 #[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
+use sentc::group::reindex::{ReindexData, ReindexItem, ReindexOptions, ReindexSearch, ReindexSortable};
+#[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
+use sentc::searchable::SearchMode;
+#[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
+use sentc::sortable::{FixedPoint, SortableValue};
+#[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
 use tokio::sync::{OnceCell, RwLock};
 
 #[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
@@ -217,6 +223,616 @@ async fn test_12_encrypt_string()
 	}
 }
 
+#[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_13_encrypt_signed_numbers_and_floats()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let g1 = GROUP_1_TEST_STATE.get().unwrap().read().await;
+
+	const INT_VALUES: [i64; 8] = [i64::MIN, -65533, -1, 0, 1, 65532, 65533, i64::MAX];
+
+	let mut past_item: Option<Vec<u64>> = None;
+
+	for v in INT_VALUES {
+		let item = g.encrypt_sortable_raw_value(&v).unwrap();
+
+		assert_eq!(item, g1.encrypt_sortable_raw_value(&v).unwrap());
+
+		if let Some(past) = past_item {
+			assert!(past < item);
+		}
+
+		past_item = Some(item);
+	}
+
+	const FLOAT_VALUES: [f64; 8] = [f64::NEG_INFINITY, -1.5, -0.1, 0.0, 0.1, 1.5, 1e300, f64::INFINITY];
+
+	let mut past_item: Option<String> = None;
+
+	for v in FLOAT_VALUES {
+		let item = g.encrypt_sortable_value(&v).unwrap().to_sortable_string();
+
+		if let Some(past) = past_item {
+			assert!(past < item);
+		}
+
+		past_item = Some(item);
+	}
+
+	//fixed-point numbers with different scales
+	let a = FixedPoint::<2>::from_parts(-1234, 2).unwrap();
+	let b = FixedPoint::<2>::from_parts(12, 0).unwrap();
+	let c = FixedPoint::<2>::from_parts(12010, 3).unwrap();
+
+	assert!(FixedPoint::<2>::from_parts(12011, 3).is_none());
+
+	let a = g.encrypt_sortable_raw_value(&a).unwrap();
+	let b = g.encrypt_sortable_raw_value(&b).unwrap();
+	let c = g.encrypt_sortable_raw_value(&c).unwrap();
+
+	assert!(a < b);
+	assert!(b < c);
+}
+
+#[cfg(all(test, feature = "chrono", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_13_x_encrypt_chrono_timestamps()
+{
+	use chrono::{DateTime, FixedOffset, Utc};
+	use sentc::sortable::SortableValue;
+
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	//before the epoch, the epoch, in one millisecond steps and far in the future
+	const MILLIS: [i64; 6] = [-86_400_000, -1, 0, 1, 2, 4_102_444_800_000];
+
+	let mut past_item: Option<Vec<u64>> = None;
+
+	for millis in MILLIS {
+		let date = DateTime::<Utc>::from_timestamp_millis(millis).unwrap();
+
+		let item = g.encrypt_sortable_raw_value(&date).unwrap();
+
+		if let Some(past) = past_item {
+			assert!(past < item, "millis: {millis}");
+		}
+
+		past_item = Some(item);
+	}
+
+	//the same point in time in another timezone got the same value
+	let date = DateTime::<Utc>::from_timestamp_millis(1_700_000_000_123).unwrap();
+	let offset_date = date.with_timezone(&FixedOffset::east_opt(5 * 3600).unwrap());
+
+	assert_eq!(date.to_sortable(), offset_date.to_sortable());
+	assert_eq!(
+		g.encrypt_sortable_raw_value(&date).unwrap(),
+		g.encrypt_sortable_raw_value(&offset_date).unwrap()
+	);
+}
+
+#[cfg(all(test, feature = "time", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_13_y_encrypt_time_timestamps()
+{
+	use sentc::sortable::SortableValue;
+	use time::{OffsetDateTime, UtcOffset};
+
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	//before the epoch, the epoch, in one millisecond steps and far in the future
+	const MILLIS: [i128; 6] = [-86_400_000, -1, 0, 1, 2, 4_102_444_800_000];
+
+	let mut past_item: Option<Vec<u64>> = None;
+
+	for millis in MILLIS {
+		let date = OffsetDateTime::from_unix_timestamp_nanos(millis * 1_000_000).unwrap();
+
+		let item = g.encrypt_sortable_raw_value(&date).unwrap();
+
+		if let Some(past) = past_item {
+			assert!(past < item, "millis: {millis}");
+		}
+
+		past_item = Some(item);
+	}
+
+	//the same point in time with another offset got the same value
+	let date = OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_123_000_000).unwrap();
+	let offset_date = date.to_offset(UtcOffset::from_hms(-5, 0, 0).unwrap());
+
+	assert_eq!(date.to_sortable(), offset_date.to_sortable());
+	assert_eq!(
+		g.encrypt_sortable_raw_value(&date).unwrap(),
+		g.encrypt_sortable_raw_value(&offset_date).unwrap()
+	);
+}
+
+#[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_14_number_range()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let g1 = GROUP_1_TEST_STATE.get().unwrap().read().await;
+
+	let range = g
+		.encrypt_sortable_number_range(Bound::Excluded(100), Bound::Included(200))
+		.unwrap();
+
+	assert!(range.exact);
+	assert_eq!(
+		range,
+		g1.encrypt_sortable_number_range(Bound::Excluded(100), Bound::Included(200))
+			.unwrap()
+	);
+
+	for (n, expected) in [
+		(0, false),
+		(99, false),
+		(100, false),
+		(101, true),
+		(150, true),
+		(200, true),
+		(201, false),
+		(65532, false),
+	] {
+		let encrypted = g1.encrypt_sortable_raw_number(n).unwrap();
+
+		assert_eq!(range.contains(encrypted), expected, "number: {n}");
+	}
+
+	let range = g
+		.encrypt_sortable_number_range(Bound::Included(100), Bound::Unbounded)
+		.unwrap();
+
+	assert!(!range.contains(g.encrypt_sortable_raw_number(99).unwrap()));
+	assert!(range.contains(g.encrypt_sortable_raw_number(100).unwrap()));
+	assert!(range.contains(g.encrypt_sortable_raw_number(65532).unwrap()));
+}
+
+#[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_15_string_range()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let range = g
+		.encrypt_sortable_string_range(Bound::Included("b"), Bound::Excluded("o"), None)
+		.unwrap();
+
+	assert!(!range.exact);
+
+	for (v, expected) in [
+		("a", false),
+		("azzz", false),
+		("b", true),
+		("baaa", true),
+		("hello", true),
+		("n", true),
+		("p", false),
+		("z", false),
+	] {
+		let encrypted = g.encrypt_sortable_raw_string(v, None).unwrap();
+
+		assert_eq!(range.contains(encrypted), expected, "string: {v}");
+	}
+
+	//the excluded bound is included because other strings can got the same number
+	assert!(range.contains(g.encrypt_sortable_raw_string("o", None).unwrap()));
+
+	//only the beginning of a string is encrypted, so longer strings with the same beginning are in the range too
+	let range = g
+		.encrypt_sortable_string_range(Bound::Unbounded, Bound::Included("hell"), None)
+		.unwrap();
+
+	assert!(range.contains(g.encrypt_sortable_raw_string("hello world", None).unwrap()));
+	assert!(!range.contains(g.encrypt_sortable_raw_string("i", None).unwrap()));
+}
+
+#[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_15_x_string_range_with_space_and_punctuation()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let range = g
+		.encrypt_sortable_string_range(Bound::Included("a"), Bound::Included("b"), None)
+		.unwrap();
+
+	//space, ! and ) are sorted before the * which is used to fill short strings
+	for v in ["a", "a ", "a!", "a)", "a c", "a*", "az", "b"] {
+		let encrypted = g.encrypt_sortable_raw_string(v, None).unwrap();
+
+		assert!(range.contains(encrypted), "string: {v}");
+	}
+
+	assert!(!range.contains(g.encrypt_sortable_raw_string("`", None).unwrap()));
+	assert!(!range.contains(g.encrypt_sortable_raw_string("c", None).unwrap()));
+}
+
+#[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_15_y_value_range()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let g1 = GROUP_1_TEST_STATE.get().unwrap().read().await;
+
+	let range = g
+		.encrypt_sortable_value_range(Bound::Excluded(&-100i64), Bound::Included(&65533i64))
+		.unwrap();
+
+	assert_eq!(
+		range,
+		g1.encrypt_sortable_value_range(Bound::Excluded(&-100i64), Bound::Included(&65533i64))
+			.unwrap()
+	);
+
+	for (v, expected) in [
+		(i64::MIN, false),
+		(-101, false),
+		(-100, false),
+		(-99, true),
+		(0, true),
+		(65533, true),
+		(65534, false),
+		(i64::MAX, false),
+	] {
+		let encrypted = g1.encrypt_sortable_raw_value(&v).unwrap();
+
+		assert_eq!(range.contains(&encrypted), expected, "number: {v}");
+	}
+
+	//floats with the sortable strings
+	let range = g
+		.encrypt_sortable_value_range(Bound::Included(&-0.5f64), Bound::Excluded(&1.5f64))
+		.unwrap();
+
+	let strings = range.to_sortable_strings();
+
+	for (v, expected) in [(-1.0f64, false), (-0.5, true), (0.0, true), (1.4999, true), (1.5, false)] {
+		let encrypted = g.encrypt_sortable_value(&v).unwrap().to_sortable_string();
+
+		assert_eq!(RangeBounds::<String>::contains(&strings, &encrypted), expected, "float: {v}");
+	}
+
+	//fixed-point numbers
+	let range = g
+		.encrypt_sortable_value_range(Bound::Unbounded, Bound::Included(&FixedPoint::<2>(1234)))
+		.unwrap();
+
+	assert!(range.contains(&g.encrypt_sortable_raw_value(&FixedPoint::<2>(-1)).unwrap()));
+	assert!(range.contains(
+		&g.encrypt_sortable_raw_value(&FixedPoint::<2>(1234))
+			.unwrap()
+	));
+	assert!(!range.contains(
+		&g.encrypt_sortable_raw_value(&FixedPoint::<2>(1235))
+			.unwrap()
+	));
+}
+
+#[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_16_encrypt_sortable_for_user()
+{
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+
+	let a = u0.encrypt_sortable_raw_number(262).unwrap();
+	let b = u0.encrypt_sortable_raw_number(263).unwrap();
+	let c = u0.encrypt_sortable_raw_number(65321).unwrap();
+
+	assert!(a < b);
+	assert!(b < c);
+
+	let a = u0.encrypt_sortable_raw_string("a", None).unwrap();
+	let b = u0.encrypt_sortable_raw_string("b", None).unwrap();
+
+	assert!(a < b);
+
+	//other user got other keys
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	assert_ne!(
+		u0.encrypt_sortable_raw_number(262).unwrap(),
+		u1.encrypt_sortable_raw_number(262).unwrap()
+	);
+
+	//the keys are exported with the user
+	let user_str = u0.to_string_ref().unwrap();
+	let imported: TestUser = user_str.parse().unwrap();
+
+	assert_eq!(
+		u0.encrypt_sortable_raw_number(262).unwrap(),
+		imported.encrypt_sortable_raw_number(262).unwrap()
+	);
+}
+
+#[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_17_reindex()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let items = vec![
+		ReindexItem {
+			id: "1".to_string(),
+			data: ReindexData::Plain("123".to_string()),
+		},
+		ReindexItem {
+			id: "2".to_string(),
+			data: ReindexData::EncryptedString(g.encrypt_string_sync("456").unwrap()),
+		},
+		ReindexItem {
+			id: "3".to_string(),
+			data: ReindexData::Encrypted(g.encrypt_sync(b"789").unwrap()),
+		},
+	];
+
+	let options = ReindexOptions {
+		search: Some(ReindexSearch {
+			full: true,
+			limit: None,
+		}),
+		sortable: Some(ReindexSortable::Number),
+		batch_size: 2,
+	};
+
+	let hmac_key_id = g.create_search("123", true, None).unwrap().key_id;
+	let sortable_key_id = g.encrypt_sortable_number(123).unwrap().key_id;
+
+	let mut progress = Vec::new();
+	let mut out = Vec::new();
+
+	let count = g
+		.reindex(&items, &options, None, |batch| {
+			assert_eq!(batch.hmac_key_id, hmac_key_id);
+			assert_eq!(batch.sortable_key_id, sortable_key_id);
+
+			progress.push((batch.done, batch.total));
+
+			for item in batch.items {
+				out.push((item.id.clone(), item.hashes.clone(), item.sortable.clone()));
+			}
+
+			Ok(())
+		})
+		.unwrap();
+
+	assert_eq!(count, 3);
+	assert_eq!(progress, [(2, 3), (3, 3)]);
+
+	for ((id, hashes, sortable), (expected_id, value)) in out.iter().zip([("1", 123), ("2", 456), ("3", 789)]) {
+		assert_eq!(id, expected_id);
+		assert_eq!(hashes, &g.create_search_raw(&value.to_string(), true, None).unwrap());
+		assert_eq!(*sortable, vec![g.encrypt_sortable_raw_number(value).unwrap()]);
+	}
+
+	//not a number
+	let items = vec![ReindexItem {
+		id: "4".to_string(),
+		data: ReindexData::Plain("abc".to_string()),
+	}];
+
+	let err = g.reindex(&items, &options, None, |_| Ok(())).unwrap_err();
+
+	assert!(matches!(err, sentc::error::SentcError::ReindexInvalidValue(id) if id == "4"));
+
+	//the index of the newest keys is up to date
+	assert!(!g.needs_reindex(Some(&hmac_key_id), Some(&sortable_key_id)));
+	assert!(g.needs_reindex(Some("old_key_id"), None));
+	assert!(g.needs_reindex(None, Some("old_key_id")));
+}
+
+#[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_17_x_reindex_sortable_values()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let reindex = |sortable: ReindexSortable, value: &str| {
+		let items = vec![ReindexItem {
+			id: "1".to_string(),
+			data: ReindexData::EncryptedString(g.encrypt_string_sync(value).unwrap()),
+		}];
+
+		let options = ReindexOptions {
+			search: None,
+			sortable: Some(sortable),
+			..Default::default()
+		};
+
+		let mut out = Vec::new();
+
+		g.reindex(&items, &options, None, |batch| {
+			out.push(batch.items[0].sortable.clone());
+			assert!(batch.items[0].hashes.is_empty());
+
+			Ok(())
+		})
+		.map(|_| out.remove(0))
+	};
+
+	assert_eq!(
+		reindex(ReindexSortable::Signed, "-42").unwrap(),
+		g.encrypt_sortable_raw_value(&-42i64).unwrap()
+	);
+	assert_eq!(
+		reindex(ReindexSortable::Float, "-10.5").unwrap(),
+		g.encrypt_sortable_raw_value(&-10.5f64).unwrap()
+	);
+
+	//fixed-point with two digits
+	let parse_price = |value: &str| {
+		let (int, frac) = value.split_once('.')?;
+		let mantissa: i64 = format!("{int}{frac}").parse().ok()?;
+
+		Some(FixedPoint::<2>::from_parts(mantissa, frac.len() as u32)?.to_sortable())
+	};
+
+	assert_eq!(
+		reindex(ReindexSortable::Value(parse_price), "12.3").unwrap(),
+		g.encrypt_sortable_raw_value(&FixedPoint::<2>(1230)).unwrap()
+	);
+
+	let err = reindex(ReindexSortable::Value(parse_price), "12").unwrap_err();
+	assert!(matches!(err, sentc::error::SentcError::ReindexInvalidValue(id) if id == "1"));
+
+	let err = reindex(ReindexSortable::Signed, "1.5").unwrap_err();
+	assert!(matches!(err, sentc::error::SentcError::ReindexInvalidValue(id) if id == "1"));
+}
+
+#[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_17_y_reindex_with_own_index()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let items: Vec<ReindexItem> = ["Jon Snow 24", "Arya Stark 11", "Sansa Stark 13"]
+		.iter()
+		.enumerate()
+		.map(|(i, value)| {
+			ReindexItem {
+				id: i.to_string(),
+				data: ReindexData::Encrypted(g.encrypt_sync(value.as_bytes()).unwrap()),
+			}
+		})
+		.collect();
+
+	let mut progress = Vec::new();
+	let mut out = Vec::new();
+
+	//a multi-field index and a sortable value from the parts of the value
+	let count = g
+		.reindex_with(
+			&items,
+			2,
+			None,
+			|_item, value| {
+				let parts: Vec<&str> = value.split(' ').collect();
+
+				let index = g
+					.search_index()
+					.field("first_name", parts[0], SearchMode::Full)
+					.field("last_name", parts[1], SearchMode::Prefix(None))
+					.build()?;
+
+				let age: u64 = parts[2].parse().unwrap();
+
+				Ok((index, g.encrypt_sortable_value(&age)?))
+			},
+			|batch| {
+				progress.push((batch.done, batch.total));
+
+				for (index, age) in batch.items {
+					assert_eq!(index.key_id, batch.hmac_key_id);
+					assert_eq!(age.key_id, batch.sortable_key_id);
+
+					out.push((index.fields[1].hashes.clone(), age.to_sortable_string()));
+				}
+
+				Ok(())
+			},
+		)
+		.unwrap();
+
+	assert_eq!(count, 3);
+	assert_eq!(progress, [(2, 3), (3, 3)]);
+
+	let query = g
+		.search_query()
+		.field("last_name", "Sta")
+		.build()
+		.unwrap();
+
+	let found: Vec<usize> = out
+		.iter()
+		.enumerate()
+		.filter(|(_, (hashes, _))| hashes.contains(&query.fields[0].hashes[0]))
+		.map(|(i, _)| i)
+		.collect();
+
+	assert_eq!(found, [1, 2]);
+
+	//the order of the ages is kept
+	assert!(out[1].1 < out[2].1);
+	assert!(out[2].1 < out[0].1);
+}
+
+#[cfg(all(test, feature = "sqlite", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_18_sqlite_columns()
+{
+	use rusqlite::{params, Connection};
+	use sentc::sqlite::{EncryptedText, SortableNumber};
+
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let conn = Connection::open_in_memory().unwrap();
+
+	conn.execute_batch(
+		"CREATE TABLE users (id TEXT PRIMARY KEY, first_name TEXT, age INTEGER, order_first_name INTEGER);
+		CREATE TABLE user_hash (item_id TEXT, hash TEXT);",
+	)
+	.unwrap();
+
+	for (id, first_name, age) in [("1", "Johnny", 60u64), ("2", "Jon", 24), ("3", "Arya", 18)] {
+		conn.execute(
+			"INSERT INTO users (id, first_name, age, order_first_name) VALUES (?1, ?2, ?3, ?4)",
+			params![
+				id,
+				g.sql_encrypt_string(first_name).unwrap(),
+				g.sql_sortable_number(age).unwrap(),
+				g.sql_sortable_string(first_name, None).unwrap()
+			],
+		)
+		.unwrap();
+
+		for hash in g.sql_create_search(first_name, false, None).unwrap() {
+			conn.execute(
+				"INSERT INTO user_hash (item_id, hash) VALUES (?1, ?2)",
+				params![id, hash],
+			)
+			.unwrap();
+		}
+	}
+
+	//search and order by the encrypted name
+	let mut stmt = conn
+		.prepare("SELECT u.first_name FROM users u, user_hash uh WHERE u.id = uh.item_id AND uh.hash = ?1 ORDER BY u.order_first_name")
+		.unwrap();
+
+	let mut rows = stmt.query(params![g.sql_search("Jo").unwrap()]).unwrap();
+	let mut names = Vec::new();
+
+	while let Some(row) = rows.next().unwrap() {
+		names.push(g.sql_get_decrypted_string(row, 0, None).unwrap());
+	}
+
+	assert_eq!(names, ["Johnny", "Jon"]);
+
+	//range query
+	let mut stmt = conn
+		.prepare("SELECT first_name, age FROM users WHERE age >= ?1 ORDER BY age")
+		.unwrap();
+
+	let rows: Vec<(EncryptedText, SortableNumber)> = stmt
+		.query_map(params![g.sql_sortable_number(20).unwrap()], |row| {
+			Ok((row.get(0)?, row.get(1)?))
+		})
+		.unwrap()
+		.collect::<Result<_, _>>()
+		.unwrap();
+
+	assert_eq!(rows.len(), 2);
+	assert_eq!(g.sql_decrypt_string(&rows[0].0, None).unwrap(), "Jon");
+	assert_eq!(g.sql_decrypt_string(&rows[1].0, None).unwrap(), "Johnny");
+	assert_eq!(rows[0].1, g.sql_sortable_number(24).unwrap());
+}
+
 #[cfg(all(test, any(feature = "std_keys", feature = "rec_keys")))]
 #[tokio::test]
 async fn zzz_clean_up()
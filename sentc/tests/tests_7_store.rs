@@ -0,0 +1,271 @@
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+use std::ops::{Bound, Deref};
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+use sentc::group::net::GroupFetchResult;
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+use sentc::searchable::SearchMode;
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+use sentc::store::{Document, EncryptedStore, MemoryStorage, Storage};
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+use tokio::sync::{OnceCell, RwLock};
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+use crate::test_mod::{TestGroup, TestUser};
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+mod test_mod;
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+struct UserState(TestUser);
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+impl Deref for UserState
+{
+	type Target = TestUser;
+
+	fn deref(&self) -> &Self::Target
+	{
+		&self.0
+	}
+}
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+struct GroupState(TestGroup);
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+impl Deref for GroupState
+{
+	type Target = TestGroup;
+
+	fn deref(&self) -> &Self::Target
+	{
+		&self.0
+	}
+}
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+static USER_0_TEST_STATE: OnceCell<RwLock<UserState>> = OnceCell::const_new();
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+static GROUP_0_TEST_STATE: OnceCell<RwLock<GroupState>> = OnceCell::const_new();
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+const USERNAME0: &str = "test0";
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+const PW: &str = "12345";
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn aaa_init_global_test()
+{
+	TestUser::register(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME0,
+		PW,
+	)
+	.await
+	.unwrap();
+	let user = TestUser::login_forced(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME0,
+		PW,
+	)
+	.await
+	.unwrap();
+
+	let group_id = user.create_group(false).await.unwrap();
+
+	let (data, res) = user.prepare_get_group(&group_id, None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	let group = user.done_get_group(data, None, None).unwrap();
+
+	USER_0_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(UserState(user)) })
+		.await;
+
+	GROUP_0_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(GroupState(group)) })
+		.await;
+}
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+fn check_store<S: Storage>(group: &TestGroup, storage: S)
+{
+	let mut store = EncryptedStore::new(group, storage);
+
+	for (id, first_name, last_name, age) in [("1", "Jon", "Snow", 24u64), ("2", "John", "Snowing", 55), ("3", "Johnny", "Depp", 60)] {
+		let data = format!("{first_name} {last_name} {age}");
+
+		store
+			.put(
+				Document::new(id, data.as_bytes())
+					.search("first_name", first_name, SearchMode::Prefix(None))
+					.search("last_name", last_name, SearchMode::Full)
+					.sortable("age", &age)
+					.sortable_string("last_name", last_name),
+			)
+			.unwrap();
+	}
+
+	//the storage only got the encrypted data
+	let encrypted = store.get_storage().get("1").unwrap().unwrap();
+	assert_ne!(encrypted, b"Jon Snow 24");
+
+	let data = store.get("1").unwrap().unwrap();
+	assert_eq!(data, b"Jon Snow 24");
+
+	//equal
+	let docs = store.find_eq("first_name", "Jon").unwrap();
+	assert_eq!(docs.len(), 1);
+	assert_eq!(docs[0].id, "1");
+
+	let docs = store.find_eq("last_name", "Snowing").unwrap();
+	assert_eq!(docs.len(), 1);
+	assert_eq!(docs[0].data, b"John Snowing 55");
+
+	//the same value in another field is not found
+	assert!(store.find_eq("first_name", "Snow").unwrap().is_empty());
+
+	//prefix
+	let mut ids: Vec<String> = store
+		.find_prefix("first_name", "Jo")
+		.unwrap()
+		.into_iter()
+		.map(|d| d.id)
+		.collect();
+	ids.sort();
+	assert_eq!(ids, ["1", "2", "3"]);
+
+	let docs = store.find_prefix("first_name", "John").unwrap();
+	assert_eq!(docs.len(), 2);
+
+	//last_name got only the full hash
+	assert!(store.find_prefix("last_name", "Sno").unwrap().is_empty());
+
+	//range, ordered by the value
+	let ids: Vec<String> = store
+		.find_range("age", Bound::Included(&24u64), Bound::Excluded(&60u64))
+		.unwrap()
+		.into_iter()
+		.map(|d| d.id)
+		.collect();
+	assert_eq!(ids, ["1", "2"]);
+
+	let ids: Vec<String> = store
+		.find_range("age", Bound::Excluded(&24u64), Bound::Unbounded)
+		.unwrap()
+		.into_iter()
+		.map(|d| d.id)
+		.collect();
+	assert_eq!(ids, ["2", "3"]);
+
+	let ids: Vec<String> = store
+		.find_string_range("last_name", Bound::Included("E"), Bound::Unbounded)
+		.unwrap()
+		.into_iter()
+		.map(|d| d.id)
+		.collect();
+	assert_eq!(ids, ["1", "2"]);
+
+	//replace the document and the index
+	store
+		.put(Document::new("1", b"Jon Stark 25").search("last_name", "Stark", SearchMode::Full))
+		.unwrap();
+
+	assert!(store.find_eq("last_name", "Snow").unwrap().is_empty());
+	assert!(store.find_eq("first_name", "Jon").unwrap().is_empty());
+	assert_eq!(store.find_eq("last_name", "Stark").unwrap().len(), 1);
+
+	//delete
+	assert!(store.delete("2").unwrap());
+	assert!(!store.delete("2").unwrap());
+	assert!(store.get("2").unwrap().is_none());
+	assert_eq!(store.find_prefix("first_name", "Jo").unwrap().len(), 1);
+}
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_10_memory_store()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	check_store(&g, MemoryStorage::new());
+}
+
+#[cfg(all(test, feature = "store_sqlite", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_11_sqlite_store()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	check_store(&g, sentc::store::SqliteStorage::open_in_memory().unwrap());
+}
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_12_reindex_store()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	let mut store = EncryptedStore::new(&g.0, MemoryStorage::new());
+
+	for (id, name) in [("1", "Jon"), ("2", "Arya"), ("3", "Sansa")] {
+		store.put(Document::new(id, name.as_bytes())).unwrap();
+	}
+
+	//the documents got no index yet
+	assert!(store.find_eq("name", "Jon").unwrap().is_empty());
+
+	let ids: Vec<String> = ["1", "2", "3", "4"].iter().map(|id| id.to_string()).collect();
+
+	let mut progress = Vec::new();
+
+	let count = store
+		.reindex(
+			&ids,
+			3,
+			|id, data| {
+				let name = String::from_utf8(data).unwrap();
+
+				Ok(Document::new(id, name.as_bytes()).search("name", &name, SearchMode::Full))
+			},
+			|batch| {
+				progress.push((batch.items.to_vec(), batch.done, batch.total));
+
+				Ok(())
+			},
+		)
+		.unwrap();
+
+	//the document 4 is not in the store
+	assert_eq!(count, 3);
+	assert_eq!(
+		progress,
+		[
+			(vec!["1".to_string(), "2".to_string(), "3".to_string()], 3, 4),
+			(vec![], 4, 4)
+		]
+	);
+
+	let docs = store.find_eq("name", "Arya").unwrap();
+	assert_eq!(docs.len(), 1);
+	assert_eq!(docs[0].data, b"Arya");
+}
+
+#[cfg(all(test, feature = "store", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn zzz_clean_up()
+{
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	g.delete_group(u0.get_jwt().unwrap()).await.unwrap();
+
+	u0.delete(PW, None, None).await.unwrap();
+}
@@ -0,0 +1,570 @@
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+use std::ops::{Deref, DerefMut};
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+use std::sync::Arc;
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+use std::time::{Duration, Instant};
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+use base64ct::{Base64UrlUnpadded, Encoding};
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+use sentc::error::SentcError;
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+use sentc::group::net::GroupFetchResult;
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+use sentc::group::rank::GroupRank;
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+use sentc::group::watcher::{GroupEvent, GroupWatcher, GroupWatcherOptions};
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+use tokio::sync::{mpsc, OnceCell, RwLock};
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+use crate::test_mod::{TestGroup, TestUser};
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+mod test_mod;
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+struct UserState(TestUser);
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+impl Deref for UserState
+{
+	type Target = TestUser;
+
+	fn deref(&self) -> &Self::Target
+	{
+		&self.0
+	}
+}
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+struct GroupState(TestGroup);
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+impl Deref for GroupState
+{
+	type Target = TestGroup;
+
+	fn deref(&self) -> &Self::Target
+	{
+		&self.0
+	}
+}
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+impl DerefMut for GroupState
+{
+	fn deref_mut(&mut self) -> &mut Self::Target
+	{
+		&mut self.0
+	}
+}
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+static USER_0_TEST_STATE: OnceCell<RwLock<UserState>> = OnceCell::const_new();
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+static USER_1_TEST_STATE: OnceCell<RwLock<UserState>> = OnceCell::const_new();
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+static USER_2_TEST_STATE: OnceCell<RwLock<UserState>> = OnceCell::const_new();
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+static GROUP_0_TEST_STATE: OnceCell<RwLock<GroupState>> = OnceCell::const_new();
+
+//the groups of user 1 which are checked by the watcher
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+static GROUPS_1_TEST_STATE: OnceCell<RwLock<Vec<TestGroup>>> = OnceCell::const_new();
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+static WATCHER_TEST_STATE: OnceCell<RwLock<GroupWatcher>> = OnceCell::const_new();
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+const USERNAME0: &str = "test0";
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+const USERNAME1: &str = "test1";
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+const USERNAME2: &str = "test2";
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+const PW: &str = "12345";
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn aaa_init_global_test()
+{
+	TestUser::register(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME0,
+		PW,
+	)
+	.await
+	.unwrap();
+	let user = TestUser::login_forced(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME0,
+		PW,
+	)
+	.await
+	.unwrap();
+
+	let group_id = user.create_group(false).await.unwrap();
+
+	let (data, res) = user.prepare_get_group(&group_id, None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	let group = user.done_get_group(data, None, None).unwrap();
+
+	USER_0_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(UserState(user)) })
+		.await;
+
+	GROUP_0_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(GroupState(group)) })
+		.await;
+
+	TestUser::register(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME1,
+		PW,
+	)
+	.await
+	.unwrap();
+	let user = TestUser::login_forced(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME1,
+		PW,
+	)
+	.await
+	.unwrap();
+	USER_1_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(UserState(user)) })
+		.await;
+
+	TestUser::register(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME2,
+		PW,
+	)
+	.await
+	.unwrap();
+	let user = TestUser::login_forced(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME2,
+		PW,
+	)
+	.await
+	.unwrap();
+	USER_2_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(UserState(user)) })
+		.await;
+
+	GROUPS_1_TEST_STATE
+		.get_or_init(|| async move { RwLock::new(Vec::new()) })
+		.await;
+
+	WATCHER_TEST_STATE
+		.get_or_init(|| {
+			async move {
+				RwLock::new(GroupWatcher::new(GroupWatcherOptions {
+					finish_key_rotation: true,
+					..Default::default()
+				}))
+			}
+		})
+		.await;
+}
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_10_invite_event()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+	let mut watcher = WATCHER_TEST_STATE.get().unwrap().write().await;
+
+	let events = watcher.check(&u1.0, &mut []).await.unwrap();
+	assert!(events.is_empty());
+
+	g.invite_user_by_id(u0.get_jwt().unwrap(), u1.get_user_id(), None, false, false)
+		.await
+		.unwrap();
+
+	//the only invite of the user, so the invite list has exactly one item
+	let events = watcher.check(&u1.0, &mut []).await.unwrap();
+
+	assert_eq!(events.len(), 1);
+
+	match &events[0] {
+		GroupEvent::Invite(invite) => assert_eq!(invite.group_id, g.get_group_id()),
+		_ => panic!("should be an invite"),
+	}
+
+	//the invite is only reported once
+	let events = watcher.check(&u1.0, &mut []).await.unwrap();
+	assert!(events.is_empty());
+}
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_11_rank_changed_event()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+	let mut groups = GROUPS_1_TEST_STATE.get().unwrap().write().await;
+	let mut watcher = WATCHER_TEST_STATE.get().unwrap().write().await;
+
+	u1.accept_group_invite(g.get_group_id()).await.unwrap();
+
+	let (data, res) = u1.prepare_get_group(g.get_group_id(), None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	groups.push(u1.done_get_group(data, None, None).unwrap());
+
+	let events = watcher.check(&u1.0, &mut groups).await.unwrap();
+	assert!(events.is_empty());
+
+	g.update_rank(u0.get_jwt().unwrap(), u1.get_user_id(), GroupRank::Manager)
+		.await
+		.unwrap();
+
+	let events = watcher.check(&u1.0, &mut groups).await.unwrap();
+
+	assert_eq!(events.len(), 1);
+
+	match &events[0] {
+		GroupEvent::RankChanged {
+			group_id,
+			old_rank,
+			new_rank,
+		} => {
+			assert_eq!(group_id, g.get_group_id());
+			assert_eq!(*old_rank, GroupRank::Member);
+			assert_eq!(*new_rank, GroupRank::Manager);
+		},
+		_ => panic!("should be a rank change"),
+	}
+
+	assert_eq!(groups[0].get_rank(), 2);
+}
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_12_finish_key_rotation()
+{
+	let mut g = GROUP_0_TEST_STATE.get().unwrap().write().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+	let mut groups = GROUPS_1_TEST_STATE.get().unwrap().write().await;
+	let mut watcher = WATCHER_TEST_STATE.get().unwrap().write().await;
+
+	g.key_rotation(u0.get_jwt().unwrap(), false, Some(&u0.0), None)
+		.await
+		.unwrap();
+
+	//wait until the server is done with the rotation
+	tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+	let events = watcher.check(&u1.0, &mut groups).await.unwrap();
+
+	assert_eq!(events.len(), 1);
+	assert!(matches!(&events[0], GroupEvent::KeyRotationFinished { group_id } if group_id == g.get_group_id()));
+
+	assert_eq!(
+		groups[0].get_newest_key().unwrap().group_key.key_id,
+		g.get_newest_key().unwrap().group_key.key_id
+	);
+
+	let events = watcher.check(&u1.0, &mut groups).await.unwrap();
+	assert!(events.is_empty());
+}
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_13_join_request_event()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+	let u2 = USER_2_TEST_STATE.get().unwrap().read().await;
+	let mut groups = GROUPS_1_TEST_STATE.get().unwrap().write().await;
+	let mut watcher = WATCHER_TEST_STATE.get().unwrap().write().await;
+
+	u2.group_join_request(g.get_group_id()).await.unwrap();
+
+	//user 1 is a manager of the group and can accept join requests.
+	//the only join request of the group, so the list has exactly one item
+	let events = watcher.check(&u1.0, &mut groups).await.unwrap();
+
+	assert_eq!(events.len(), 1);
+
+	match &events[0] {
+		GroupEvent::JoinRequest {
+			group_id,
+			request,
+		} => {
+			assert_eq!(group_id, g.get_group_id());
+			assert_eq!(request.user_id, u2.get_user_id());
+		},
+		_ => panic!("should be a join request"),
+	}
+
+	//the join request is only reported once
+	let events = watcher.check(&u1.0, &mut groups).await.unwrap();
+	assert!(events.is_empty());
+}
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_14_check_other_groups_after_a_group_error()
+{
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+	let mut groups = GROUPS_1_TEST_STATE.get().unwrap().write().await;
+	let mut watcher = WATCHER_TEST_STATE.get().unwrap().write().await;
+
+	//a group which is deleted after user 1 fetched it
+	let group_id = u0.create_group(false).await.unwrap();
+	let (data, _) = u0.prepare_get_group(&group_id, None).await.unwrap();
+	let deleted_group = u0.done_get_group(data, None, None).unwrap();
+
+	deleted_group
+		.invite_auto(
+			u0.get_jwt().unwrap(),
+			u1.get_user_id(),
+			u1.get_newest_exported_public_key().unwrap(),
+			None,
+		)
+		.await
+		.unwrap();
+
+	let (data, res) = u1.prepare_get_group(&group_id, None).await.unwrap();
+
+	assert!(matches!(res, GroupFetchResult::Ok));
+
+	groups.insert(0, u1.done_get_group(data, None, None).unwrap());
+
+	deleted_group
+		.delete_group(u0.get_jwt().unwrap())
+		.await
+		.unwrap();
+
+	g.update_rank(u0.get_jwt().unwrap(), u1.get_user_id(), GroupRank::Admin)
+		.await
+		.unwrap();
+
+	let events = watcher.check(&u1.0, &mut groups).await.unwrap();
+
+	assert_eq!(events.len(), 2);
+	assert!(matches!(&events[0], GroupEvent::GroupError { group_id: id, .. } if id == &group_id));
+
+	match &events[1] {
+		GroupEvent::RankChanged {
+			group_id,
+			old_rank,
+			new_rank,
+		} => {
+			assert_eq!(group_id, g.get_group_id());
+			assert_eq!(*old_rank, GroupRank::Manager);
+			assert_eq!(*new_rank, GroupRank::Admin);
+		},
+		_ => panic!("should be a rank change"),
+	}
+
+	groups.remove(0);
+}
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_15_run_in_interval()
+{
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	//the watcher locks the user, so it needs its own instance
+	let user = RwLock::new(
+		TestUser::login_forced(
+			"http://127.0.0.1:3002".into(),
+			"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+			USERNAME1,
+			PW,
+		)
+		.await
+		.unwrap(),
+	);
+	let groups: RwLock<Vec<Arc<RwLock<TestGroup>>>> = RwLock::new(Vec::new());
+
+	let mut invite_groups = Vec::with_capacity(2);
+
+	for _ in 0..2 {
+		let group_id = u0.create_group(false).await.unwrap();
+		let (data, _) = u0.prepare_get_group(&group_id, None).await.unwrap();
+		invite_groups.push(u0.done_get_group(data, None, None).unwrap());
+	}
+
+	invite_groups[0]
+		.invite_user_by_id(u0.get_jwt().unwrap(), u1.get_user_id(), None, false, false)
+		.await
+		.unwrap();
+
+	let interval = Duration::from_millis(500);
+
+	let watcher = GroupWatcher::new(GroupWatcherOptions {
+		interval,
+		..Default::default()
+	});
+
+	let (sender, receiver) = mpsc::channel(8);
+
+	let handle = async {
+		let mut receiver = receiver;
+
+		//the first check is done directly
+		let event = receiver.recv().await.unwrap();
+		let first = Instant::now();
+
+		assert!(matches!(&event, GroupEvent::Invite(invite) if invite.group_id == invite_groups[0].get_group_id()));
+
+		invite_groups[1]
+			.invite_user_by_id(u0.get_jwt().unwrap(), u1.get_user_id(), None, false, false)
+			.await
+			.unwrap();
+
+		let event = receiver.recv().await.unwrap();
+
+		assert!(first.elapsed() >= interval - Duration::from_millis(50));
+		assert!(matches!(&event, GroupEvent::Invite(invite) if invite.group_id == invite_groups[1].get_group_id()));
+
+		//the watcher stops after the receiver is dropped
+	};
+
+	tokio::join!(watcher.run(&user, &groups, sender), handle);
+
+	for group in invite_groups {
+		group.delete_group(u0.get_jwt().unwrap()).await.unwrap();
+	}
+}
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+fn expire_jwt(jwt: &str) -> String
+{
+	let parts: Vec<&str> = jwt.split('.').collect();
+
+	let mut claims: serde_json::Value = serde_json::from_slice(&Base64UrlUnpadded::decode_vec(parts[1]).unwrap()).unwrap();
+	claims["exp"] = 0.into();
+
+	let claims = Base64UrlUnpadded::encode_string(&serde_json::to_vec(&claims).unwrap());
+
+	format!("{}.{}.{}", parts[0], claims, parts[2])
+}
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn test_16_run_with_backoff_and_jwt_refresh()
+{
+	let u0 = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	let mut user = TestUser::login_forced(
+		"http://127.0.0.1:3002".into(),
+		"5zMb6zs3dEM62n+FxjBilFPp+j9e7YUFA+7pi6Hi",
+		USERNAME1,
+		PW,
+	)
+	.await
+	.unwrap();
+
+	//the changed jwt is expired for the client, and the server won't refresh it because the signature is wrong
+	let jwt = user.get_jwt_sync().to_string();
+	user.set_jwt(expire_jwt(&jwt));
+
+	let user = RwLock::new(user);
+	let groups: RwLock<Vec<Arc<RwLock<TestGroup>>>> = RwLock::new(Vec::new());
+
+	let group_id = u0.create_group(false).await.unwrap();
+	let (data, _) = u0.prepare_get_group(&group_id, None).await.unwrap();
+	let invite_group = u0.done_get_group(data, None, None).unwrap();
+
+	invite_group
+		.invite_user_by_id(u0.get_jwt().unwrap(), u1.get_user_id(), None, false, false)
+		.await
+		.unwrap();
+
+	let interval = Duration::from_millis(100);
+	let max_interval = Duration::from_millis(400);
+
+	let watcher = GroupWatcher::new(GroupWatcherOptions {
+		interval,
+		max_interval,
+		..Default::default()
+	});
+
+	let (sender, receiver) = mpsc::channel(8);
+
+	let handle = async {
+		let mut receiver = receiver;
+
+		let mut last: Option<Instant> = None;
+
+		//the interval is doubled after each error until the max. interval: 200ms, 400ms, 400ms
+		for expected in [None, Some(200), Some(400), Some(400)] {
+			let event = receiver.recv().await.unwrap();
+
+			//the watcher tried to refresh the jwt, so the error is from the refresh and not the expired jwt
+			match event {
+				GroupEvent::Error(SentcError::JwtExpired) => panic!("jwt should be refreshed"),
+				GroupEvent::Error(_) => {},
+				_ => panic!("should be an error"),
+			}
+
+			if let (Some(last), Some(expected)) = (last, expected) {
+				assert!(last.elapsed() >= Duration::from_millis(expected - 20));
+			}
+
+			last = Some(Instant::now());
+		}
+
+		user.write().await.set_jwt(jwt.clone());
+
+		//the check works again with a valid jwt
+		let event = receiver.recv().await.unwrap();
+
+		assert!(matches!(&event, GroupEvent::Invite(invite) if invite.group_id == invite_group.get_group_id()));
+	};
+
+	tokio::join!(watcher.run(&user, &groups, sender), handle);
+
+	invite_group
+		.delete_group(u0.get_jwt().unwrap())
+		.await
+		.unwrap();
+}
+
+#[cfg(all(test, feature = "watcher", any(feature = "std_keys", feature = "rec_keys")))]
+#[tokio::test]
+async fn zzz_clean_up()
+{
+	let u = USER_0_TEST_STATE.get().unwrap().read().await;
+	let u1 = USER_1_TEST_STATE.get().unwrap().read().await;
+
+	let g = GROUP_0_TEST_STATE.get().unwrap().read().await;
+
+	g.delete_group(u.get_jwt().unwrap()).await.unwrap();
+
+	u.delete(PW, None, None).await.unwrap();
+
+	u1.delete(PW, None, None).await.unwrap();
+
+	let u2 = USER_2_TEST_STATE.get().unwrap().read().await;
+
+	u2.delete(PW, None, None).await.unwrap();
+}